@@ -1,15 +1,16 @@
 use crate::{
+    data::get_data_dir,
     printinfo,
     utils::{get_root_dir_path, resource_exists, ResourceType},
-    verbose, PROJECT_DIRS,
+    verbose,
 };
 use anyhow::{Context, Result};
 use std::{fs, path::Path};
 
 pub fn clear(all: bool) -> Result<()> {
     if !all {
-        let path = PROJECT_DIRS.data_dir();
-        if !resource_exists(path, ResourceType::Dir, false)? {
+        let path = get_data_dir();
+        if !resource_exists(&path, ResourceType::Dir, false)? {
             if verbose() {
                 printinfo!("directory does not exist");
             }
@@ -17,8 +18,7 @@ pub fn clear(all: bool) -> Result<()> {
             return Ok(());
         }
 
-        fs::remove_dir_all(PROJECT_DIRS.data_dir())
-            .with_context(|| "Failed to delete data directory")?;
+        fs::remove_dir_all(get_data_dir()).with_context(|| "Failed to delete data directory")?;
     } else {
         let path = get_root_dir_path().with_context(|| "Failed to get root directory path")?;
         if !resource_exists(Path::new(&path), ResourceType::Dir, false)? {
@@ -0,0 +1,105 @@
+use crate::{
+    config::{
+        get_default_values, get_user_config_path, validate_value, ConfigFile, ConfigOrigin,
+        LayeredConfig,
+    },
+    printinfo, printwarning,
+};
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+/// Prints the value of a configuration key, merged from every layer
+pub fn config_get(key: String, show_origin: bool) -> Result<()> {
+    let config = LayeredConfig::load()?;
+    let value = match config.values.get(&key) {
+        Some(data) => data,
+        None => bail!("Unknown configuration key '{}'", key),
+    };
+
+    if show_origin {
+        println!("{}\t{}", config.get_origin(&key), format_value(value));
+    } else {
+        println!("{}", format_value(value));
+    }
+
+    Ok(())
+}
+
+/// Sets a configuration key in the user's configuration file
+///
+/// The value is read as JSON where possible (e.g. `10`, `true` or `["-n"]`), and as a plain string otherwise
+pub fn config_set(key: String, value: String) -> Result<()> {
+    if !get_default_values().contains_key(&key) {
+        bail!("Unknown configuration key '{}'", key);
+    }
+
+    let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+    validate_value(&key, &value)
+        .with_context(|| format!("Invalid value for configuration key '{}'", key))?;
+
+    let mut file = ConfigFile::read(&get_user_config_path()?)?;
+    file.values.insert(key.clone(), value);
+    file.write()?;
+
+    warn_if_overridden(&key, &file)
+}
+
+/// Removes a configuration key from the user's configuration file, so that it falls back to the layers below
+pub fn config_unset(key: String) -> Result<()> {
+    let mut file = ConfigFile::read(&get_user_config_path()?)?;
+    if file.values.remove(&key).is_none() {
+        printinfo!("'{}' is not set in {}", key, file.path.display());
+        return Ok(());
+    }
+    file.write()
+}
+
+/// Prints every configuration key and its value, merged from every layer
+pub fn config_list(show_origin: bool) -> Result<()> {
+    let config = LayeredConfig::load()?;
+
+    for (key, value) in &config.values {
+        if show_origin {
+            println!(
+                "{}\t{} = {}",
+                config.get_origin(key),
+                key,
+                format_value(value)
+            );
+        } else {
+            println!("{} = {}", key, format_value(value));
+        }
+    }
+
+    Ok(())
+}
+
+/// Formats a value for printing, with strings unquoted and everything else as JSON
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(data) => data.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// Warns if a key that was just set in the user's configuration file is also set by a layer above it (a project file or environment variable), so the change has no effect
+fn warn_if_overridden(key: &str, file: &ConfigFile) -> Result<()> {
+    let config = LayeredConfig::load()?;
+    let origin = config.get_origin(key);
+    let overridden = match &origin {
+        ConfigOrigin::Default => false,
+        ConfigOrigin::File { path, .. } => path != &file.path,
+        ConfigOrigin::Environment(_) => true,
+    };
+
+    if overridden {
+        printwarning!(
+            "'{}' is also set in {}, which takes precedence over {}",
+            key,
+            origin,
+            file.path.display()
+        );
+    }
+
+    Ok(())
+}
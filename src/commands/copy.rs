@@ -0,0 +1,33 @@
+use crate::{
+    data::{read_window_data, write_window_data},
+    utils::{check_overwrite, record_revision, validate_name},
+    CONFIG,
+};
+use anyhow::{ensure, Context, Result};
+use chrono::Utc;
+
+/// Copies an arrangement to a new name, as a new arrangement (with its own creation time and history)
+pub fn copy(source: String, destination: String, force: bool) -> Result<()> {
+    validate_name(&source)?;
+    validate_name(&destination)?;
+    ensure!(
+        source != destination,
+        "An arrangement cannot be copied to itself"
+    );
+
+    let mut data = read_window_data(&source)
+        .with_context(|| format!("Failed to read arrangement '{}'", source))?;
+    check_overwrite(&destination, force)?;
+
+    let now = Utc::now();
+    data.name = destination;
+    data.created_at = Some(now);
+    data.updated_at = Some(now);
+    data.created_on_host = gethostname::gethostname().into_string().ok();
+    data.windough_version = Some(env!("CARGO_PKG_VERSION").to_string());
+
+    write_window_data(&data, CONFIG.backup_count).with_context(|| "Failed to write arrangement")?;
+    record_revision(data, &format!("copy of {}", source));
+
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use crate::{
+    data::read_window_data,
+    platform::WindowSystem,
+    utils::{capture_arrangement, diff_arrangements, validate_name, EntryChange},
+    CONFIG,
+};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+/// What `diff` compares an arrangement with
+pub enum DiffTarget<'a> {
+    /// Another saved arrangement
+    Arrangement(String),
+    /// The windows that are open now
+    Desktop(&'a dyn WindowSystem),
+}
+
+/// What `diff --json` prints
+#[derive(Serialize)]
+struct DiffOutput {
+    from: String,
+    /// The arrangement compared with, or `None` for the open windows
+    to: Option<String>,
+    changes: Vec<EntryChange>,
+}
+
+/// Prints how a saved arrangement differs from another one, or from the windows that are open now
+///
+/// Open windows are read without restoring those that are minimized, so only the state of a minimized window is compared, not its position, size or monitor
+///
+/// # Arguments
+///
+/// * `name` - The name of the arrangement to compare
+/// * `target` - What to compare it with
+/// * `json` - Print the differences as JSON
+///
+/// # Errors
+///
+/// Returns an error if a name is not valid, an arrangement could not be read or the open windows could not be captured
+pub fn diff(name: String, target: DiffTarget, json: bool) -> Result<()> {
+    validate_name(&name)?;
+    let from_data = read_window_data(&name)
+        .with_context(|| format!("Failed to read arrangement '{}'", name))?;
+
+    let (to, changes) = match target {
+        DiffTarget::Arrangement(other_name) => {
+            validate_name(&other_name)?;
+            let to_data = read_window_data(&other_name)
+                .with_context(|| format!("Failed to read arrangement '{}'", other_name))?;
+            let changes = diff_arrangements(&from_data.data, &to_data.data, false);
+            (Some(other_name), changes)
+        }
+        DiffTarget::Desktop(window_system) => {
+            // Open windows are captured as `save` would capture them (except that minimized windows are left minimized), and only compared by where they are, as they have no launch settings
+            let (to_data, _) = capture_arrangement(
+                window_system,
+                String::new(),
+                &CONFIG.get_window_filters(),
+                false,
+            )?;
+            let changes = diff_arrangements(&from_data.data, &to_data.data, true);
+            (None, changes)
+        }
+    };
+
+    if json {
+        let output = DiffOutput {
+            from: name,
+            to,
+            changes,
+        };
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&output)
+                .with_context(|| "Error formatting differences as JSON")?
+        );
+        return Ok(());
+    }
+
+    let to = match to {
+        Some(data) => format!("'{}'", data),
+        None => String::from("the open windows"),
+    };
+    if changes.is_empty() {
+        println!("'{}' and {} have no differences", name, to);
+        return Ok(());
+    }
+
+    println!("'{}' -> {}:", name, to);
+    for change in &changes {
+        match change {
+            EntryChange::Added {
+                index,
+                application_path,
+            } => println!("+ #{} {}", index, application_path),
+            EntryChange::Removed {
+                index,
+                application_path,
+            } => println!("- #{} {}", index, application_path),
+            EntryChange::Changed {
+                index,
+                application_path,
+                moved,
+                resized,
+                fields,
+            } => {
+                println!("~ #{} {}", index, application_path);
+                if let Some(moved) = moved {
+                    println!(
+                        "    moved: {:+}{:+} -> {:+}{:+} (by {:+}, {:+})",
+                        moved.before.left,
+                        moved.before.top,
+                        moved.after.left,
+                        moved.after.top,
+                        moved.delta.left,
+                        moved.delta.top
+                    );
+                }
+                if let Some(resized) = resized {
+                    println!(
+                        "    resized: {}x{} -> {}x{} (by {:+}, {:+})",
+                        resized.before.width,
+                        resized.before.height,
+                        resized.after.width,
+                        resized.after.height,
+                        resized.delta.width,
+                        resized.delta.height
+                    );
+                }
+                for field in fields {
+                    println!(
+                        "    {}: {} -> {}",
+                        field.field,
+                        format_value(field.before.as_ref()),
+                        format_value(field.after.as_ref())
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Formats a value for printing, with strings unquoted, everything else as JSON and `-` if it is not set
+fn format_value(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(data)) => data.clone(),
+        Some(data) => data.to_string(),
+        None => String::from("-"),
+    }
+}
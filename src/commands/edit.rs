@@ -0,0 +1,663 @@
+use super::{show, ShowFormat};
+use crate::{
+    data::{
+        migrate, parse_window_data, read_window_data, write_window_data, WindowData,
+        WindowDataEntry, WindowPosition, WindowSize,
+    },
+    printerror, printinfo,
+    utils::{
+        confirm, find_monitor, find_unknown_key, get_application_name, get_monitor_placement,
+        record_revision, validate_name,
+    },
+    CONFIG,
+};
+use anyhow::{bail, ensure, Context, Result};
+use chrono::Utc;
+use clap::{Args, ValueEnum};
+use regex::Regex;
+use serde_json::Value;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
+
+// How many random names are tried for the copy of an arrangement before giving up
+const TEMP_FILE_ATTEMPTS: usize = 16;
+
+/// What `edit` changes in an arrangement
+pub enum EditOperation {
+    /// Open the arrangement in the user's editor
+    Editor,
+    ListEntries,
+    SetEntry(EntrySelector, EntryFields),
+    /// Add an entry for the application at the given path
+    AddEntry(String, EntryFields),
+    RemoveEntry(EntrySelector),
+    /// Move an entry to the given index
+    MoveEntry(EntrySelector, usize),
+}
+
+/// Picks out an entry of an arrangement, by its index or by the name or path of its application
+#[derive(Clone)]
+pub enum EntrySelector {
+    Index(usize),
+    Application(String),
+}
+
+impl FromStr for EntrySelector {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Ok(match value.parse() {
+            Ok(index) => EntrySelector::Index(index),
+            Err(_) => EntrySelector::Application(value.to_string()),
+        })
+    }
+}
+
+/// A window's size and position, written as `WIDTHxHEIGHT+X+Y` (e.g. `800x600+0+0` or `800x600-10+20`)
+#[derive(Clone, Copy)]
+pub struct Geometry {
+    pub position: WindowPosition,
+    pub size: WindowSize,
+}
+
+impl FromStr for Geometry {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let pattern = Regex::new(r"^(\d+)x(\d+)([+-]\d+)([+-]\d+)$").unwrap();
+        let captures = match pattern.captures(value.trim()) {
+            Some(data) => data,
+            None => bail!(
+                "Expected geometry as 'WIDTHxHEIGHT+X+Y' (e.g. '800x600+0+0'), but found '{}'",
+                value
+            ),
+        };
+        let number = |index: usize| {
+            captures[index]
+                .parse::<i32>()
+                .with_context(|| format!("Number '{}' in geometry is too large", &captures[index]))
+        };
+
+        Ok(Geometry {
+            position: WindowPosition {
+                left: number(3)?,
+                top: number(4)?,
+            },
+            size: WindowSize {
+                width: number(1)?,
+                height: number(2)?,
+            },
+        })
+    }
+}
+
+/// The state a window is placed in
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum WindowState {
+    Normal,
+    Minimized,
+    Maximized,
+}
+
+/// The values of an entry that can be changed on the command line (those that are not given are left as they are)
+#[derive(Args)]
+pub struct EntryFields {
+    #[arg(
+        long,
+        value_name = "BOOL",
+        help = "Whether the application is launched if it has no window open"
+    )]
+    pub launch: Option<bool>,
+    #[arg(
+        long,
+        value_name = "BOOL",
+        help = "Whether the window is moved and resized"
+    )]
+    pub reposition: Option<bool>,
+    #[arg(
+        long,
+        value_name = "ARGS",
+        num_args = 0..,
+        allow_hyphen_values = true,
+        help = "The arguments to launch the application with (takes every value after it, so put it last; give no values to clear them)"
+    )]
+    pub args: Option<Vec<String>>,
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "The directory to launch the application in"
+    )]
+    pub working_directory: Option<String>,
+    #[arg(
+        long,
+        value_name = "WxH+X+Y",
+        help = "The window's size and position (e.g. '800x600+0+0')"
+    )]
+    pub geometry: Option<Geometry>,
+    #[arg(long, value_enum, help = "The state the window is placed in")]
+    pub state: Option<WindowState>,
+    #[arg(
+        long,
+        value_name = "REGEX",
+        help = "A regular expression a window's title must match to be placed by this entry (an empty pattern clears it)"
+    )]
+    pub title_pattern: Option<String>,
+    #[arg(
+        long,
+        value_name = "MILLISECONDS",
+        help = "How long the entry's window is waited for, instead of the configured wait"
+    )]
+    pub timeout: Option<usize>,
+}
+
+pub fn edit(name: String, operation: EditOperation) -> Result<()> {
+    validate_name(&name)?;
+
+    let mut data = read_window_data(&name)?;
+
+    match operation {
+        EditOperation::Editor => {
+            data = match edit_in_editor(&data)? {
+                Some(data) => data,
+                None => {
+                    printinfo!("no changes were made");
+                    return Ok(());
+                }
+            }
+        }
+        EditOperation::ListEntries => return show(name, ShowFormat::Table, false),
+        EditOperation::SetEntry(selector, fields) => {
+            let index = find_entry(&data, &selector)?;
+            set_fields(&mut data, index, fields)?;
+            printinfo!("changed entry {}", index);
+        }
+        EditOperation::AddEntry(application_path, fields) => {
+            let geometry = match fields.geometry {
+                Some(data) => data,
+                None => bail!("--geometry is required when adding an entry"),
+            };
+            data.data.push(WindowDataEntry {
+                application_path,
+                application_args: Vec::new(),
+                launch_command: None,
+                working_directory: None,
+                launch: true,
+                reposition: true,
+                position: geometry.position,
+                size: geometry.size,
+                minimized: false,
+                maximised: false,
+                window_title: None,
+                title_pattern: None,
+                window_class: None,
+                instance: None,
+                workspace: None,
+                monitor: None,
+                timeout: None,
+                ready_when: None,
+            });
+            let index = data.data.len() - 1;
+            set_fields(&mut data, index, fields)?;
+            printinfo!("added entry {}", index);
+        }
+        EditOperation::RemoveEntry(selector) => {
+            let index = find_entry(&data, &selector)?;
+            data.data.remove(index);
+            printinfo!("removed entry {}", index);
+        }
+        EditOperation::MoveEntry(selector, to) => {
+            let index = find_entry(&data, &selector)?;
+            ensure!(
+                to < data.data.len(),
+                "Cannot move entry to index {} - '{}' only has {} entries",
+                to,
+                name,
+                data.data.len()
+            );
+            let entry = data.data.remove(index);
+            data.data.insert(to, entry);
+            printinfo!("moved entry {} to {}", index, to);
+        }
+    }
+
+    data.updated_at = Some(Utc::now());
+    write_window_data(&data, CONFIG.backup_count).with_context(|| "Failed to write arrangement")?;
+    record_revision(data, "edit");
+
+    Ok(())
+}
+
+/// Finds the index of the entry a selector picks out
+///
+/// # Errors
+///
+/// Returns an error if no entry matches, or if an application name or path matches more than one entry
+fn find_entry(data: &WindowData, selector: &EntrySelector) -> Result<usize> {
+    match selector {
+        EntrySelector::Index(index) => {
+            ensure!(
+                *index < data.data.len(),
+                "'{}' has no entry {} (it has {} entries)",
+                data.name,
+                index,
+                data.data.len()
+            );
+            Ok(*index)
+        }
+        EntrySelector::Application(application) => {
+            let indices: Vec<usize> = data
+                .data
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| {
+                    entry.application_path.eq_ignore_ascii_case(application)
+                        || get_application_name(&entry.application_path)
+                            .eq_ignore_ascii_case(application)
+                })
+                .map(|(index, _)| index)
+                .collect();
+            match indices.as_slice() {
+                [index] => Ok(*index),
+                [] => bail!("'{}' has no entry for '{}'", data.name, application),
+                _ => bail!(
+                    "'{}' has more than one entry for '{}' (entries {}) - use an index instead",
+                    data.name,
+                    application,
+                    indices
+                        .iter()
+                        .map(usize::to_string)
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            }
+        }
+    }
+}
+
+/// Changes the values of an entry that were given on the command line
+///
+/// # Errors
+///
+/// Returns an error if the title pattern is not a valid regular expression
+fn set_fields(data: &mut WindowData, index: usize, fields: EntryFields) -> Result<()> {
+    let monitors = &data.monitors;
+    let entry = &mut data.data[index];
+
+    if let Some(launch) = fields.launch {
+        entry.launch = launch;
+    }
+    if let Some(reposition) = fields.reposition {
+        entry.reposition = reposition;
+    }
+    if let Some(args) = fields.args {
+        entry.application_args = args;
+    }
+    if let Some(working_directory) = fields.working_directory {
+        entry.working_directory = Some(working_directory).filter(|dir| !dir.is_empty());
+    }
+    if let Some(geometry) = fields.geometry {
+        entry.position = geometry.position;
+        entry.size = geometry.size;
+        // Where the window is on its monitor is worked out again, as `save` would, so that it is still remapped if monitors change
+        entry.monitor = find_monitor(monitors, &entry.position, &entry.size)
+            .map(|monitor| get_monitor_placement(monitor, &entry.position, &entry.size));
+    }
+    if let Some(state) = fields.state {
+        entry.minimized = state == WindowState::Minimized;
+        entry.maximised = state == WindowState::Maximized;
+    }
+    if let Some(title_pattern) = fields.title_pattern {
+        entry.title_pattern = Some(title_pattern).filter(|pattern| !pattern.is_empty());
+    }
+    if let Some(timeout) = fields.timeout {
+        entry.timeout = Some(timeout);
+    }
+
+    validate_entry(entry)
+}
+
+/// Checks the values of an entry that are not checked when it is parsed
+fn validate_entry(entry: &WindowDataEntry) -> Result<()> {
+    let title_patterns = [
+        entry.title_pattern.as_ref(),
+        entry
+            .ready_when
+            .as_ref()
+            .and_then(|readiness| readiness.title_pattern.as_ref()),
+    ];
+    for title_pattern in title_patterns.into_iter().flatten() {
+        Regex::new(title_pattern)
+            .with_context(|| format!("Invalid title pattern '{}'", title_pattern))?;
+    }
+
+    ensure!(
+        entry.size.width > 0 && entry.size.height > 0,
+        "Window size must be greater than 0x0"
+    );
+
+    Ok(())
+}
+
+/// Opens a pretty-printed copy of an arrangement in the user's editor, and checks it once the editor is closed; if it is not valid, the user can edit it again or give up (like `visudo`)
+///
+/// # Errors
+///
+/// Returns an error if the copy could not be written or read, the editor could not be run, or the user gave up on an invalid copy
+///
+/// # Returns
+///
+/// The edited arrangement, or `None` if it was not changed
+fn edit_in_editor(data: &WindowData) -> Result<Option<WindowData>> {
+    let original = serde_json::to_string_pretty(data)
+        .with_context(|| "Error formatting arrangement as JSON")?;
+    let file_path = create_temp_file(&data.name, &original)?;
+
+    let result = edit_file_until_valid(&file_path, &original, &data.name);
+    let _ = fs::remove_file(&file_path);
+    result
+}
+
+/// Writes a copy of an arrangement to a new file in the temporary directory, which only the user can read
+///
+/// The file has a random name and is never opened if it already exists, so another user cannot read it, or replace it with a link to make Windough write elsewhere
+///
+/// # Errors
+///
+/// Returns an error if the file could not be created or written
+fn create_temp_file(name: &str, contents: &str) -> Result<PathBuf> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    for _ in 0..TEMP_FILE_ATTEMPTS {
+        let file_path =
+            env::temp_dir().join(format!("windough-{}-{:016x}.json", name, fastrand::u64(..)));
+        let mut file = match options.open(&file_path) {
+            Ok(data) => data,
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => {
+                return Err(error).with_context(|| "Failed to create copy of arrangement")
+            }
+        };
+        if let Err(error) = file.write_all(contents.as_bytes()) {
+            let _ = fs::remove_file(&file_path);
+            return Err(error).with_context(|| "Failed to write copy of arrangement");
+        }
+        return Ok(file_path);
+    }
+
+    bail!("Failed to create copy of arrangement: no unused file name was found")
+}
+
+fn edit_file_until_valid(
+    file_path: &Path,
+    original: &str,
+    name: &str,
+) -> Result<Option<WindowData>> {
+    loop {
+        run_editor(file_path)?;
+
+        let contents =
+            fs::read_to_string(file_path).with_context(|| "Failed to read edited arrangement")?;
+        if contents == original {
+            return Ok(None);
+        }
+
+        let error = match parse_edited(&contents, name) {
+            Ok(data) => return Ok(Some(data)),
+            Err(error) => error,
+        };
+        printerror!("{:#}", error.context("the edited arrangement is not valid"));
+        match confirm("Edit it again? (otherwise your changes are discarded)")? {
+            Some(true) => (),
+            _ => bail!("The arrangement was not changed"),
+        }
+    }
+}
+
+/// Parses and checks an edited arrangement
+///
+/// Unlike when an arrangement is read, keys that are not part of it are not ignored, as they are most likely misspelled
+fn parse_edited(contents: &str, name: &str) -> Result<WindowData> {
+    let data = parse_window_data(contents)?;
+
+    let edited: Value =
+        serde_json::from_str(contents).with_context(|| "Error parsing file contents as JSON")?;
+    if let Some(key_path) = find_unknown_key::<WindowData>(&migrate(edited)?) {
+        bail!("Unknown key '{}'", key_path);
+    }
+
+    ensure!(
+        data.name == name,
+        "'name' must stay '{}' (it cannot be changed by editing)",
+        name
+    );
+    for (index, entry) in data.data.iter().enumerate() {
+        validate_entry(entry).with_context(|| format!("Invalid entry {}", index))?;
+    }
+    Ok(data)
+}
+
+/// Runs the user's editor (`$VISUAL` or `$EDITOR`, falling back to Notepad on Windows and `vi` elsewhere) on a file, waiting for it to close
+fn run_editor(file_path: &Path) -> Result<()> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| String::from(if cfg!(windows) { "notepad" } else { "vi" }));
+
+    let status = get_editor_command(&editor, file_path)
+        .status()
+        .with_context(|| format!("Failed to run editor '{}'", editor))?;
+    ensure!(
+        status.success(),
+        "Editor '{}' exited with {} - the arrangement was not changed",
+        editor,
+        status
+    );
+
+    Ok(())
+}
+
+/// Gets the command that runs an editor on a file
+///
+/// An editor that is the path to a program (which may have spaces in it) is run directly. Anything else is run by the shell, so that the editor can have arguments of its own (e.g. `code --wait`) and be quoted
+fn get_editor_command(editor: &str, file_path: &Path) -> Command {
+    if Path::new(editor).is_file() {
+        let mut command = Command::new(editor);
+        command.arg(file_path);
+        return command;
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        // `cmd /C` strips the outer quotes, leaving the editor followed by the quoted path
+        let mut command = Command::new("cmd");
+        command
+            .arg("/C")
+            .raw_arg(format!("\"{} \"{}\"\"", editor, file_path.display()));
+        command
+    }
+
+    #[cfg(not(windows))]
+    {
+        // The path is passed as an argument to the shell rather than put in the script, so it is never interpreted by it
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(format!("{} \"$@\"", editor))
+            .arg("sh")
+            .arg(file_path);
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_private_temp_file_with_random_name() {
+        let first = create_temp_file("test", "{}").unwrap();
+        let second = create_temp_file("test", "{}").unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "{}");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&first).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        fs::remove_file(first).unwrap();
+        fs::remove_file(second).unwrap();
+    }
+
+    /// Writes an editor to a directory with a space in its path, which writes the arguments it was run with into the file it was given
+    #[cfg(unix)]
+    fn write_fake_editor(test_name: &str) -> (PathBuf, PathBuf) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir_path = env::temp_dir().join(format!(
+            "windough editor-{}-{}",
+            std::process::id(),
+            test_name
+        ));
+        fs::create_dir_all(&dir_path).unwrap();
+        let editor_path = dir_path.join("fake editor");
+        fs::write(
+            &editor_path,
+            "#!/bin/sh\nfor last; do :; done\nprintf '%s' \"$*\" > \"$last\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&editor_path, fs::Permissions::from_mode(0o700)).unwrap();
+
+        (dir_path.join("arrangement.json"), editor_path)
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn runs_editor_with_spaces_in_its_path() {
+        let (file_path, editor_path) = write_fake_editor("path");
+
+        let status = get_editor_command(editor_path.to_str().unwrap(), &file_path)
+            .status()
+            .unwrap();
+
+        assert!(status.success());
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            file_path.to_str().unwrap()
+        );
+        fs::remove_dir_all(file_path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn runs_quoted_editor_with_arguments_through_shell() {
+        let (file_path, editor_path) = write_fake_editor("arguments");
+        let editor = format!("'{}' --wait", editor_path.display());
+
+        let status = get_editor_command(&editor, &file_path).status().unwrap();
+
+        assert!(status.success());
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            format!("--wait {}", file_path.display())
+        );
+        fs::remove_dir_all(file_path.parent().unwrap()).unwrap();
+    }
+
+    /// Gets an edited arrangement named `test`, with `extra` added to its first entry
+    fn edited(extra: &str) -> String {
+        format!(
+            r#"{{
+                "version": 2,
+                "name": "test",
+                "tags": ["work"],
+                "settings": {{ "backoff": "fixed" }},
+                "data": [{{
+                    "application_path": "/usr/bin/editor",
+                    "application_args": [],
+                    "launch": true,
+                    "reposition": true,
+                    "position": {{ "top": 0, "left": 0 }},
+                    "size": {{ "width": 800, "height": 600 }},
+                    "minimized": false,
+                    "maximised": false,
+                    "title_pattern": null{}
+                }}]
+            }}"#,
+            extra
+        )
+    }
+
+    /// Gets why an edited arrangement was rejected
+    fn parse_error(contents: &str) -> String {
+        match parse_edited(contents, "test") {
+            Ok(_) => panic!("the edited arrangement was accepted"),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert_eq!(
+            parse_error(&edited(r#", "lanch": false"#)),
+            "Unknown key 'data[0].lanch'"
+        );
+        assert_eq!(
+            parse_error(&edited(
+                r#", "ready_when": { "stable_for": 500, "min_width": 800 }"#
+            )),
+            "Unknown key 'data[0].ready_when.min_width'"
+        );
+        assert_eq!(
+            parse_error(&edited("").replacen("\"tags\"", "\"tag\"", 1)),
+            "Unknown key 'tag'"
+        );
+        // Misspelled keys are found even when their value is left out when the arrangement is serialized
+        assert_eq!(
+            parse_error(&edited(r#", "aplication_args": []"#)),
+            "Unknown key 'data[0].aplication_args'"
+        );
+        assert_eq!(
+            parse_error(&edited(r#", "windw_title": null"#)),
+            "Unknown key 'data[0].windw_title'"
+        );
+        assert_eq!(
+            parse_error(&edited("").replacen("\"settings\"", "\"setings\"", 1)),
+            "Unknown key 'setings'"
+        );
+    }
+
+    #[test]
+    fn accepts_known_keys_that_are_null_or_empty() {
+        let contents = edited(r#", "window_title": null, "launch_command": [], "workspace": null"#)
+            .replacen(
+                "\"tags\": [\"work\"]",
+                "\"tags\": [], \"description\": null",
+                1,
+            );
+
+        assert!(parse_edited(&contents, "test").is_ok());
+    }
+
+    #[test]
+    fn accepts_only_unrenamed_arrangement() {
+        assert!(parse_edited(&edited(""), "other").is_err());
+        assert!(parse_edited(&edited(""), "test").is_ok());
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{
+    data::read_revisions,
+    printinfo,
+    utils::{print_table, validate_name},
+};
+use anyhow::Result;
+use chrono::Local;
+
+/// Prints the revisions of an arrangement, oldest first
+pub fn history(name: String) -> Result<()> {
+    validate_name(&name)?;
+
+    let revisions = read_revisions(&name)?;
+    if revisions.is_empty() {
+        printinfo!("'{}' has no revision history", name);
+        return Ok(());
+    }
+
+    let rows: Vec<Vec<String>> = revisions
+        .into_iter()
+        .map(|revision| {
+            vec![
+                revision.number.to_string(),
+                revision
+                    .saved_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+                revision.source,
+                revision.window_data.data.len().to_string(),
+                revision.summary,
+            ]
+        })
+        .collect();
+    print_table(
+        &["REVISION", "SAVED", "SOURCE", "ENTRIES", "CHANGES"],
+        &rows,
+    );
+
+    Ok(())
+}
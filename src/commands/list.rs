@@ -1,74 +1,202 @@
 use crate::{
-    data::WindowData,
+    data::{get_data_dir, parse_window_data, read_last_loaded, WindowData},
     printinfo, printwarning,
-    utils::{resource_exists, ResourceType},
-    verbose, PROJECT_DIRS,
+    utils::{print_table, resource_exists, ResourceType},
+    verbose,
 };
 use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
 use std::fs::{self, DirEntry};
 
-pub fn list() -> Result<()> {
-    let path = PROJECT_DIRS.data_dir();
-    if !resource_exists(path, ResourceType::Dir, false)? {
+/// The order `list` prints arrangements in
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum ListSort {
+    /// Alphabetically by name
+    Name,
+    /// Most recently loaded (or modified, if never loaded) first
+    Recent,
+}
+
+/// The command-line options of `list`
+pub struct ListOptions {
+    pub long: bool,
+    pub sort: ListSort,
+    pub json: bool,
+    /// Only list arrangements that have all of these tags
+    pub tags: Vec<String>,
+    /// List the files that are not valid arrangements instead
+    pub broken: bool,
+}
+
+/// What `list` shows about a saved arrangement
+#[derive(Serialize)]
+struct ArrangementSummary {
+    name: String,
+    entries: usize,
+    modified: Option<DateTime<Utc>>,
+    last_loaded: Option<DateTime<Utc>>,
+    description: Option<String>,
+    tags: Vec<String>,
+    created_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+    created_on_host: Option<String>,
+}
+
+/// A file in the data directory that is not a valid arrangement
+#[derive(Serialize)]
+struct BrokenFile {
+    file: String,
+    reason: String,
+}
+
+pub fn list(options: ListOptions) -> Result<()> {
+    let path = get_data_dir();
+    if !resource_exists(&path, ResourceType::Dir, false)? {
         if verbose() {
             printinfo!("data directory does not exist");
         }
 
+        if options.json {
+            print_json(&Vec::<ArrangementSummary>::new())?;
+        }
         return Ok(());
     }
 
-    let mut names: Vec<String> = Vec::new();
-    let mut potentially_missing_entries = false;
+    let last_loaded = match read_last_loaded() {
+        Ok(data) => data,
+        Err(error) => {
+            if verbose() {
+                printwarning!("{:#}", error);
+            }
+            Default::default()
+        }
+    };
+
+    let mut summaries: Vec<ArrangementSummary> = Vec::new();
+    let mut broken_files: Vec<BrokenFile> = Vec::new();
 
     for entry in fs::read_dir(path).with_context(|| "Failed to read directory")? {
-        match entry.with_context(|| "failed to get item from directory") {
-            Ok(item) => match evaluate_item(item) {
-                Ok(data) => {
-                    if let Some(name) = data {
-                        names.push(name)
-                    }
-                }
-                Err(error) => {
-                    if verbose() {
-                        printwarning!("{}", error);
-                    }
-
-                    potentially_missing_entries = true;
-                    continue;
-                }
-            },
+        let item = match entry.with_context(|| "failed to get item from directory") {
+            Ok(data) => data,
             Err(error) => {
-                if verbose() {
-                    printwarning!("{}", error);
-                }
-
-                potentially_missing_entries = true;
+                broken_files.push(BrokenFile {
+                    file: String::from("?"),
+                    reason: format!("{:#}", error),
+                });
                 continue;
             }
         };
+        let file = item.file_name().to_string_lossy().to_string();
+
+        match evaluate_item(item) {
+            Ok(Some((window_data, modified))) => summaries.push(ArrangementSummary {
+                last_loaded: last_loaded.get(&window_data.name).copied(),
+                name: window_data.name,
+                entries: window_data.data.len(),
+                modified,
+                description: window_data.description,
+                tags: window_data.tags,
+                created_at: window_data.created_at,
+                updated_at: window_data.updated_at,
+                created_on_host: window_data.created_on_host,
+            }),
+            Ok(None) => (),
+            Err(error) => broken_files.push(BrokenFile {
+                file,
+                reason: format!("{:#}", error),
+            }),
+        }
+    }
+
+    if options.broken {
+        broken_files.sort_by(|a, b| a.file.cmp(&b.file));
+        if options.json {
+            return print_json(&broken_files);
+        }
+        let rows: Vec<Vec<String>> = broken_files
+            .into_iter()
+            .map(|broken_file| vec![broken_file.file, broken_file.reason])
+            .collect();
+        if !rows.is_empty() {
+            print_table(&["FILE", "REASON"], &rows);
+        }
+        return Ok(());
+    }
+
+    summaries.retain(|summary| options.tags.iter().all(|tag| summary.tags.contains(tag)));
+    match options.sort {
+        ListSort::Name => summaries.sort_by(|a, b| a.name.cmp(&b.name)),
+        ListSort::Recent => summaries.sort_by(|a, b| {
+            let last_used = |summary: &ArrangementSummary| summary.last_loaded.or(summary.modified);
+            last_used(b)
+                .cmp(&last_used(a))
+                .then_with(|| a.name.cmp(&b.name))
+        }),
+    }
+
+    if options.json {
+        print_json(&summaries)?;
+    } else if options.long {
+        let rows: Vec<Vec<String>> = summaries
+            .iter()
+            .map(|summary| {
+                vec![
+                    summary.name.clone(),
+                    summary.entries.to_string(),
+                    format_time(summary.modified),
+                    format_time(summary.last_loaded),
+                    summary.tags.join(","),
+                    summary.description.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        print_table(
+            &[
+                "NAME",
+                "ENTRIES",
+                "MODIFIED",
+                "LAST LOADED",
+                "TAGS",
+                "DESCRIPTION",
+            ],
+            &rows,
+        );
+    } else {
+        let names: Vec<&str> = summaries
+            .iter()
+            .map(|summary| summary.name.as_str())
+            .collect();
+        println!("{}", names.join("\n"));
     }
 
-    println!("{}", names.join("\n"));
-    if potentially_missing_entries {
-        printinfo!("some items may be missing from this list - for more details, run this command in verbose mode");
+    if !broken_files.is_empty() {
+        if verbose() {
+            for broken_file in &broken_files {
+                printwarning!("{}: {}", broken_file.file, broken_file.reason);
+            }
+        } else {
+            printinfo!("some items may be missing from this list - to see which files could not be read and why, run 'windough list --broken'");
+        }
     }
 
     Ok(())
 }
 
-fn evaluate_item(item: DirEntry) -> Result<Option<String>> {
+fn evaluate_item(item: DirEntry) -> Result<Option<(WindowData, Option<DateTime<Utc>>)>> {
     let metadata = item
         .metadata()
         .with_context(|| "failed to get item metadata")?;
-    if !metadata.is_file() {
+    let path = item.path();
+    if !metadata.is_file()
+        || path.extension().and_then(|extension| extension.to_str()) != Some("json")
+    {
         return Ok(None);
     }
 
-    let path = item.path();
-
     let json_string = fs::read_to_string(&path).with_context(|| "error reading data from file")?;
-    let window_data: WindowData = serde_json::from_str(&json_string)
-        .with_context(|| "error parsing file contents as JSON")?;
+    let window_data = parse_window_data(&json_string)?;
 
     let name_from_path_option = path.file_stem();
     let name_from_path = match name_from_path_option {
@@ -79,5 +207,26 @@ fn evaluate_item(item: DirEntry) -> Result<Option<String>> {
         bail!("'name' property in file does not match expected name");
     }
 
-    Ok(Some(window_data.name))
+    let modified = metadata.modified().ok().map(DateTime::<Utc>::from);
+
+    Ok(Some((window_data, modified)))
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).with_context(|| "Error formatting list as JSON")?
+    );
+    Ok(())
+}
+
+/// Formats a time in the local time zone, or `-` if there is none
+fn format_time(time: Option<DateTime<Utc>>) -> String {
+    match time {
+        Some(time) => time
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        None => String::from("-"),
+    }
 }
@@ -1,154 +1,1385 @@
 use crate::{
-    data::WindowData,
+    config::{Backoff, OtherWindows, WindowFilters},
+    data::{
+        prune_undo_history, read_window_data, record_last_loaded, write_undo_snapshot, EntryReport,
+        EntryStatus, LoadReport, PlacementDrift, ReportFormat, UndoSnapshot, WindowData,
+        WindowDataEntry, WindowPosition, WindowSize,
+    },
+    platform::{WindowId, WindowSystem},
     printwarning,
     utils::{
-        get_module_path_from_window, get_module_paths_from_windows, get_open_windows,
-        launch_application, reposition_and_resize_window, resource_exists, validate_name,
-        ResourceType,
+        apply_window_rules, capture_arrangement, check_readiness, filter_open_windows,
+        get_monitors, get_open_window_details, match_windows, remap_to_monitor, validate_name,
+        verify_placements, GeometryHistory, LoadSettings, Placement, WaitPolicy,
     },
-    verbose, CONFIG, PROJECT_DIRS,
+    verbose, CONFIG,
 };
-use anyhow::{ensure, Context, Result};
-use std::{fs, iter, thread::sleep, time::Duration};
-use winapi::{
-    shared::windef::HWND,
-    um::winuser::{
-        IsZoomed, PostMessageW, ShowWindow, SW_MAXIMIZE, SW_MINIMIZE, SW_RESTORE, WM_CLOSE,
-    },
+use anyhow::{bail, Context, Result};
+use std::{
+    collections::VecDeque,
+    path::Path,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
-pub fn load(
-    name: String,
-    close_others: bool,
-    minimize_others: bool,
-    retry_count_flag: Option<usize>,
-    retry_interval_flag: Option<usize>,
-) -> Result<()> {
+/// Why an entry whose application is not running was not waited for
+const NOT_LAUNCHED_MESSAGE: &str = "not running and launching is disabled";
+
+/// The command-line options of `load`
+pub struct LoadOptions {
+    pub other_windows: Option<OtherWindows>,
+    pub launch_concurrency: Option<usize>,
+    pub retry_count: Option<usize>,
+    pub retry_interval: Option<usize>,
+    pub timeout: Option<usize>,
+    pub backoff: Option<Backoff>,
+    pub dry_run: bool,
+    pub report_format: ReportFormat,
+}
+
+pub fn load(window_system: &dyn WindowSystem, name: String, options: LoadOptions) -> Result<()> {
     validate_name(&name)?;
 
-    let file_path = PROJECT_DIRS.data_dir().join(format!("{}.json", name));
-    ensure!(
-        resource_exists(&file_path, ResourceType::File, false)?,
-        "File does not exist"
+    let mut window_data = read_window_data(&name)?;
+    // Rules are applied again, as they may have changed since the arrangement was saved
+    for entry in &mut window_data.data {
+        apply_window_rules(entry, &CONFIG.rules)?;
+    }
+
+    let arrangement_settings = window_data.settings.clone().unwrap_or_default();
+    let wait_policy = WaitPolicy::from_config(
+        &CONFIG,
+        &arrangement_settings,
+        options.retry_count,
+        options.retry_interval,
+        options.timeout,
+        options.backoff,
+    );
+    let settings = LoadSettings::from_config(
+        &CONFIG,
+        &arrangement_settings,
+        wait_policy,
+        options.other_windows,
+        options.launch_concurrency,
     );
-    let json_string =
-        fs::read_to_string(file_path).with_context(|| "Error reading data from file")?;
 
-    let window_data: WindowData = serde_json::from_str(&json_string)
-        .with_context(|| "Error parsing file contents as JSON")?;
+    if options.dry_run {
+        return print_load_plan(window_system, &window_data, &settings);
+    }
 
-    ensure!(
-        window_data.name == name,
-        "'name' property in file does not match expected name"
-    );
+    let undo_snapshot = if CONFIG.undo_history_size > 0 {
+        take_undo_snapshot(window_system, &name, &settings)
+    } else {
+        None
+    };
 
-    let initial_open_windows = get_open_windows()?;
-    let running_module_paths = get_module_paths_from_windows(&initial_open_windows);
+    let report = arrange_windows(window_system, &window_data, &settings)?;
 
-    // Launch Applications
-    for window in &window_data.data {
-        if window.launch && !running_module_paths.contains(&Some(window.application_path.clone())) {
-            match launch_application(&window.application_path, &window.application_args) {
-                Ok(_) => (),
-                Err(error) => {
-                    if verbose() {
-                        printwarning!("{:?}", error);
-                    }
+    if let Err(error) = record_last_loaded(&name)
+        .with_context(|| "failed to record when the arrangement was loaded")
+    {
+        printwarning!("{:#}", error);
+    }
+
+    if let Some((mut snapshot, windows)) = undo_snapshot {
+        let entry_indices = |changed_windows: &[WindowId]| -> Vec<usize> {
+            windows
+                .iter()
+                .enumerate()
+                .filter(|(_, window)| changed_windows.contains(window))
+                .map(|(index, _)| index)
+                .collect()
+        };
+        snapshot.closed = entry_indices(&report.closed_windows);
+        snapshot.minimized = entry_indices(&report.minimized_windows);
+        if let Err(error) =
+            write_undo_snapshot(&snapshot).with_context(|| "failed to update undo snapshot")
+        {
+            printwarning!("{:#}", error);
+        }
+    }
+
+    print_load_report(&report, options.report_format)?;
+
+    let failed_count = report.failed_count();
+    if failed_count > 0 {
+        bail!(
+            "{} of {} entries could not be loaded",
+            failed_count,
+            report.entries.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Captures the current arrangement of open windows into the undo history, so that the load can be undone
+///
+/// A failure is only warned about, as it should not stop the arrangement from being loaded
+///
+/// # Arguments
+///
+/// * `window_system` - The window system to capture windows from
+/// * `loaded` - The name of the arrangement about to be loaded
+/// * `settings` - How the arrangement is about to be loaded
+///
+/// # Returns
+///
+/// The snapshot and the window each of its entries was captured from, or `None` if it could not be taken
+fn take_undo_snapshot(
+    window_system: &dyn WindowSystem,
+    loaded: &str,
+    settings: &LoadSettings,
+) -> Option<(UndoSnapshot, Vec<WindowId>)> {
+    // Only the windows a load could change are captured
+    let result = capture_arrangement(
+        window_system,
+        loaded.to_string(),
+        &get_load_filters(settings),
+        true,
+    )
+    .and_then(|(window_data, windows)| {
+        let snapshot = UndoSnapshot {
+            taken_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .with_context(|| "System time is before the Unix epoch")?
+                .as_millis() as u64,
+            loaded: loaded.to_string(),
+            window_data,
+            closed: Vec::new(),
+            minimized: Vec::new(),
+        };
+        write_undo_snapshot(&snapshot)?;
+        prune_undo_history(CONFIG.undo_history_size)?;
+        Ok((snapshot, windows))
+    });
+
+    match result.with_context(|| "failed to save undo snapshot - this load cannot be undone") {
+        Ok(data) => Some(data),
+        Err(error) => {
+            printwarning!("{:#}", error);
+            None
+        }
+    }
+}
+
+/// Gets the filters for windows a load must leave alone (which are neither matched to entries, nor closed or minimized)
+fn get_load_filters(settings: &LoadSettings) -> WindowFilters {
+    WindowFilters {
+        include: Vec::new(),
+        exclude: settings.exclude_windows.clone(),
+    }
+}
+
+/// Prints what loading an arrangement would do, without launching, moving or closing anything
+///
+/// # Arguments
+///
+/// * `window_system` - The window system the arrangement would be loaded in
+/// * `window_data` - The arrangement to plan
+/// * `settings` - How the arrangement would be loaded
+///
+/// # Errors
+///
+/// Returns an error if the open windows could not be enumerated or an entry's title pattern is invalid
+fn print_load_plan(
+    window_system: &dyn WindowSystem,
+    window_data: &WindowData,
+    settings: &LoadSettings,
+) -> Result<()> {
+    let current_monitors = get_monitors(window_system);
+
+    let open_windows = window_system.get_open_windows()?;
+    let mut open_window_details = get_open_window_details(window_system, &open_windows);
+    filter_open_windows(
+        window_system,
+        &mut open_window_details,
+        &get_load_filters(settings),
+        &current_monitors,
+    )?;
+    let mut matched_windows = vec![None; window_data.data.len()];
+    for (entry_index, window_index) in match_windows(
+        &window_data.data,
+        &vec![true; window_data.data.len()],
+        &open_window_details,
+    )? {
+        matched_windows[entry_index] = Some(&open_window_details[window_index]);
+    }
+
+    println!(
+        "Plan for '{}' (dry run - nothing will be changed)",
+        window_data.name
+    );
+    if settings.launch_concurrency > 0 {
+        println!(
+            "Applications are launched {} at a time",
+            settings.launch_concurrency
+        );
+    }
+    println!();
+    println!("Entries:");
+    for (entry_index, (window, matched_window)) in
+        window_data.data.iter().zip(matched_windows).enumerate()
+    {
+        println!("  [{}] {}", entry_index, window.application_path);
+
+        match matched_window {
+            Some(open_window) => println!(
+                "      already running - matched to window '{}'",
+                open_window.title
+            ),
+            None if window.launch => {
+                let mut launch = String::from("      launch");
+                if let Some(launch_command) = &window.launch_command {
+                    launch += &format!(" as {:?}", launch_command);
+                } else if !window.application_args.is_empty() {
+                    launch += &format!(" with args {:?}", window.application_args);
                 }
+                if let Some(working_directory) = &window.working_directory {
+                    launch += &format!(" in '{}'", working_directory);
+                }
+                println!("{}", launch);
+            }
+            None => {
+                println!("      skipped - {}", NOT_LAUNCHED_MESSAGE);
+                continue;
+            }
+        }
+
+        if !window.reposition {
+            println!("      left where it is (repositioning is disabled)");
+            continue;
+        }
+
+        if let Some(readiness) = &window.ready_when {
+            let mut conditions = Vec::new();
+            if let Some(title_pattern) = &readiness.title_pattern {
+                conditions.push(format!("title matches '{}'", title_pattern));
+            }
+            if let Some(min_size) = &readiness.min_size {
+                conditions.push(format!("at least {}x{}", min_size.width, min_size.height));
+            }
+            if let Some(stable_for) = readiness.stable_for {
+                conditions.push(format!("geometry stable for {}ms", stable_for));
             }
+            if let Some(process_age) = readiness.process_age {
+                conditions.push(format!("process running for {}s", process_age));
+            }
+            if !conditions.is_empty() {
+                println!("      wait until ready: {}", conditions.join(", "));
+            }
+        }
+
+        let (position, size) = remap_to_monitor(window, &window_data.monitors, &current_monitors);
+        let mut placement = format!(
+            "      move to {}x{}+{}+{}",
+            size.width, size.height, position.left, position.top
+        );
+        if let Some(workspace) = &window.workspace {
+            placement += &format!(" on workspace '{}'", workspace.name);
         }
+        if window.maximised {
+            placement += ", then maximize";
+        }
+        if window.minimized {
+            placement += ", then minimize";
+        }
+        println!("{}", placement);
     }
 
-    let retry_count = match retry_count_flag {
-        Some(value) => value,
-        None => CONFIG.retry_count,
-    };
-    let retry_interval = match retry_interval_flag {
-        Some(value) => value,
-        None => CONFIG.retry_interval,
+    // Only windows of applications that are not in the arrangement at all are closed or minimized
+    let other_windows: Vec<_> = open_window_details
+        .iter()
+        .filter(|open_window| {
+            !window_data
+                .data
+                .iter()
+                .any(|data| data.application_path == open_window.module_path)
+        })
+        .collect();
+    let other_action = match settings.other_windows {
+        OtherWindows::Leave => None,
+        OtherWindows::Close => Some("close"),
+        OtherWindows::Minimize => Some("minimize"),
     };
 
-    println!("{} {}", retry_count, retry_interval);
+    if let Some(action) = other_action {
+        println!();
+        println!("Other windows:");
+        if other_windows.is_empty() {
+            println!("  none");
+        }
+        for open_window in other_windows {
+            println!(
+                "  {} '{}' ({})",
+                action, open_window.title, open_window.module_path
+            );
+        }
+    }
 
-    let mut retry_attempts = 0;
-    // For windows included in window_data
-    let mut windows_to_retry = Vec::from_iter(iter::repeat(true).take(window_data.data.len()));
-    // For other windows (not in window_data)
-    let mut windows_to_ignore: Vec<HWND> = Vec::new();
+    Ok(())
+}
 
-    while retry_attempts < retry_count && windows_to_retry.contains(&true) {
-        sleep(Duration::from_millis(retry_interval as u64));
+/// Launches the applications in an arrangement and moves their windows into place
+///
+/// Windows that are already open are placed straight away, and the rest are placed as they appear. Each entry's window is waited for until its own deadline, looking at the open windows whenever a window event arrives (or after the wait policy's interval, if none does)
+///
+/// # Arguments
+///
+/// * `window_system` - The window system to arrange windows in
+/// * `window_data` - The arrangement to apply
+/// * `settings` - How to load the arrangement (what to do with other windows, how long windows are waited for, and so on). Nothing is read from the global configuration, so this decides everything
+///
+/// # Errors
+///
+/// Returns an error if the open windows could not be enumerated or an entry's title pattern is invalid. Entries that could not be launched or placed do not cause an error, but are marked as failed in the returned report
+pub fn arrange_windows(
+    window_system: &dyn WindowSystem,
+    window_data: &WindowData,
+    settings: &LoadSettings,
+) -> Result<LoadReport> {
+    let wait_policy = &settings.wait_policy;
+    let current_monitors = get_monitors(window_system);
 
-        let open_windows = get_open_windows()?;
+    let initial_open_windows = window_system.get_open_windows()?;
+    let load_filters = get_load_filters(settings);
+    let mut initial_open_window_details =
+        get_open_window_details(window_system, &initial_open_windows);
+    filter_open_windows(
+        window_system,
+        &mut initial_open_window_details,
+        &load_filters,
+        &current_monitors,
+    )?;
+    let mut already_running = vec![false; window_data.data.len()];
+    for (entry_index, _) in match_windows(
+        &window_data.data,
+        &vec![true; window_data.data.len()],
+        &initial_open_window_details,
+    )? {
+        already_running[entry_index] = true;
+    }
 
-        // Reposition & Resize Windows
-        for hwnd in open_windows {
-            if windows_to_ignore.contains(&hwnd) {
-                continue;
+    let mut entry_reports: Vec<EntryReport> = window_data
+        .data
+        .iter()
+        .enumerate()
+        .map(|(index, window)| {
+            let (status, message) = if already_running[index] {
+                (EntryStatus::AlreadyRunning, None)
+            } else if !window.launch {
+                (
+                    EntryStatus::Skipped,
+                    Some(String::from(NOT_LAUNCHED_MESSAGE)),
+                )
+            } else {
+                (EntryStatus::NotFound, None)
+            };
+            EntryReport {
+                index,
+                application_path: window.application_path.clone(),
+                status,
+                attempts: 0,
+                drift: None,
+                message,
             }
+        })
+        .collect();
+
+    let start = window_system.now();
+    let overall_deadline = wait_policy.overall_timeout.map(|timeout| start + timeout);
 
-            let module_path = get_module_path_from_window(&hwnd)
-                .with_context(|| "Failed to get module path from window handle")?;
-            if module_path.to_lowercase().starts_with("c:\\windows")
-                || module_path.to_lowercase().starts_with("c:/windows")
+    // Entries whose application is still to be launched, in order. Until an entry is launched, it has no deadline
+    let mut launch_queue: VecDeque<usize> = (0..window_data.data.len())
+        .filter(|&index| window_data.data[index].launch && !already_running[index])
+        .collect();
+    // Skipped entries are not waited for either, but are still placed if their window opens while others are
+    let mut deadlines: Vec<Option<Instant>> = window_data
+        .data
+        .iter()
+        .enumerate()
+        .map(|(index, window)| {
+            if launch_queue.contains(&index) || entry_reports[index].status == EntryStatus::Skipped
             {
+                None
+            } else {
+                Some(get_entry_deadline(
+                    window_system,
+                    window,
+                    &entry_reports[index],
+                    wait_policy,
+                    overall_deadline,
+                ))
+            }
+        })
+        .collect();
+
+    let mut attempts = 0;
+    // For windows included in window_data
+    let mut windows_to_retry = vec![true; window_data.data.len()];
+    // For other windows (not in window_data), and windows that have already been placed
+    let mut windows_to_ignore: Vec<WindowId> = Vec::new();
+    let mut closed_windows: Vec<WindowId> = Vec::new();
+    let mut minimized_windows: Vec<WindowId> = Vec::new();
+    // Why each entry's window is being held back, if it is
+    let mut not_ready_reasons: Vec<Option<String>> = vec![None; window_data.data.len()];
+    let mut geometry_history = GeometryHistory::new();
+    // The windows placed in the current pass, to be verified at the end of it
+    let mut placements: Vec<(usize, Placement)> = Vec::new();
+
+    loop {
+        not_ready_reasons.fill(None);
+        attempts += 1;
+
+        // Launch Applications, waiting for no more than `launch_concurrency` at once
+        while let Some(&entry_index) = launch_queue.front() {
+            if !can_launch(settings, &entry_reports, &windows_to_retry) {
+                break;
+            }
+            launch_queue.pop_front();
+
+            // Its window may have been opened by something else in the meantime
+            if !windows_to_retry[entry_index] {
                 continue;
             }
 
-            let window_data_index_option = window_data
+            let window = &window_data.data[entry_index];
+            let entry_report = &mut entry_reports[entry_index];
+            launch_entry(window_system, window, entry_report);
+            deadlines[entry_index] = Some(get_entry_deadline(
+                window_system,
+                window,
+                entry_report,
+                wait_policy,
+                overall_deadline,
+            ));
+        }
+
+        // Details are gathered for every window (not just unhandled ones) so that instance ordinals line up with those saved
+        let open_windows = window_system.get_open_windows()?;
+        let mut open_window_details = get_open_window_details(window_system, &open_windows);
+        open_window_details.retain(|open_window| !windows_to_ignore.contains(&open_window.window));
+        filter_open_windows(
+            window_system,
+            &mut open_window_details,
+            &load_filters,
+            &current_monitors,
+        )?;
+
+        // Handle windows of applications that are not in the arrangement
+        open_window_details.retain(|open_window| {
+            if window_data
                 .data
                 .iter()
-                .position(|data| module_path == data.application_path);
-            let window_data_index = match window_data_index_option {
-                Some(data) => data,
-                None => {
-                    unsafe {
-                        if close_others {
-                            PostMessageW(hwnd, WM_CLOSE, 0, 0);
-                        } else if minimize_others {
-                            ShowWindow(hwnd, SW_MINIMIZE);
-                        }
-                    }
+                .any(|data| data.application_path == open_window.module_path)
+            {
+                return true;
+            }
 
-                    windows_to_ignore.push(hwnd);
-                    continue;
-                }
+            let result = match settings.other_windows {
+                OtherWindows::Leave => Ok(()),
+                OtherWindows::Close => window_system
+                    .close(open_window.window)
+                    .map(|_| closed_windows.push(open_window.window)),
+                OtherWindows::Minimize => window_system
+                    .minimize(open_window.window)
+                    .map(|_| minimized_windows.push(open_window.window)),
             };
-
-            if !windows_to_retry[window_data_index] {
-                continue;
+            if let Err(error) = result {
+                if verbose() {
+                    printwarning!("{:?}", error);
+                }
             }
 
+            windows_to_ignore.push(open_window.window);
+            false
+        });
+
+        // Reposition & Resize Windows
+        for (window_data_index, open_window_index) in
+            match_windows(&window_data.data, &windows_to_retry, &open_window_details)?
+        {
+            let open_window = &open_window_details[open_window_index];
+            let window_id = open_window.window;
             let window = &window_data.data[window_data_index];
+            let entry_report = &mut entry_reports[window_data_index];
+
+            // Hold the window back (and look again next time, in case it is replaced) until it is ready
+            if let (true, Some(readiness)) = (window.reposition, &window.ready_when) {
+                not_ready_reasons[window_data_index] =
+                    check_readiness(window_system, open_window, readiness, &mut geometry_history)
+                        .unwrap_or_else(|error| Some(format!("{:#}", error)));
+                if not_ready_reasons[window_data_index].is_some() {
+                    continue;
+                }
+            }
+
             windows_to_retry[window_data_index] = false;
+            windows_to_ignore.push(window_id);
+            entry_report.attempts = attempts;
 
             if !window.reposition {
+                // A window that appeared without being launched was opened by something else
+                if entry_report.status != EntryStatus::Launched {
+                    entry_report.status = EntryStatus::AlreadyRunning;
+                    entry_report.message = None;
+                }
                 continue;
             }
 
-            unsafe {
-                // Restore window to visible position
-                ShowWindow(hwnd, SW_RESTORE);
-                // Un-maximize window so it can be moved properly
-                if IsZoomed(hwnd) != 0 {
-                    ShowWindow(hwnd, SW_RESTORE);
+            let (position, size) =
+                remap_to_monitor(window, &window_data.monitors, &current_monitors);
+            match place_window(window_system, window_id, window, &position, &size) {
+                Ok(_) => {
+                    entry_report.status = EntryStatus::Placed;
+                    entry_report.message = None;
+
+                    // Minimized and maximized windows are not where they were placed, and some windows cannot be placed at all
+                    if settings.verify_placement
+                        && !window.minimized
+                        && !window.maximised
+                        && window_system.can_reposition(window_id).unwrap_or(false)
+                    {
+                        placements.push((
+                            window_data_index,
+                            Placement {
+                                window: window_id,
+                                position,
+                                size,
+                            },
+                        ));
+                    }
+                }
+                Err(error) => {
+                    if verbose() {
+                        printwarning!("{:?}", error);
+                    }
+                    entry_report.status = EntryStatus::RepositionFailed;
+                    entry_report.message = Some(format!("{:#}", error));
                 }
             }
+        }
 
-            reposition_and_resize_window(hwnd, &window.position, &window.size)?;
+        if !placements.is_empty() {
+            let (entry_indices, placements): (Vec<usize>, Vec<Placement>) =
+                placements.drain(..).unzip();
+            let results = verify_placements(
+                window_system,
+                &placements,
+                settings.verify_tolerance,
+                settings.verify_retries,
+                settings.verify_delay,
+            );
+            for ((entry_index, placement), result) in
+                entry_indices.into_iter().zip(&placements).zip(results)
+            {
+                report_verification(
+                    settings,
+                    &mut entry_reports[entry_index],
+                    &window_data.data[entry_index],
+                    placement,
+                    result,
+                );
+            }
+        }
 
-            unsafe {
-                if window.maximised {
-                    ShowWindow(hwnd, SW_MAXIMIZE);
-                }
-                if window.minimized {
-                    ShowWindow(hwnd, SW_MINIMIZE);
-                }
+        // Give up on entries whose deadline has passed
+        let now = window_system.now();
+        for (entry_index, entry_report) in entry_reports.iter_mut().enumerate() {
+            // An entry that is still to be launched is only given up on when the whole load times out
+            let deadline = deadlines[entry_index].or(overall_deadline);
+            if !windows_to_retry[entry_index]
+                || entry_report.status == EntryStatus::Skipped
+                || deadline.is_none_or(|deadline| deadline > now)
+            {
+                continue;
+            }
+            windows_to_retry[entry_index] = false;
+            entry_report.attempts = attempts;
+            if deadlines[entry_index].is_none() {
+                entry_report.message = Some(String::from(
+                    "the load timed out before it could be launched",
+                ));
+            } else if let Some(reason) = &not_ready_reasons[entry_index] {
+                entry_report.status = EntryStatus::NotReady;
+                entry_report.message = Some(format!("window did not become ready: {}", reason));
+            } else if entry_report.status != EntryStatus::LaunchFailed {
+                entry_report.status = EntryStatus::NotFound;
+                entry_report.message = Some(format!(
+                    "no matching window appeared within {:.1}s",
+                    (now - start).as_secs_f64()
+                ));
             }
         }
 
-        retry_attempts += 1;
+        // Another application can be launched straight away when one stops being waited for
+        if !launch_queue.is_empty() && can_launch(settings, &entry_reports, &windows_to_retry) {
+            continue;
+        }
+
+        let next_deadline = windows_to_retry
+            .iter()
+            .zip(&deadlines)
+            .zip(&entry_reports)
+            .filter(|((pending, _), entry_report)| {
+                **pending && entry_report.status != EntryStatus::Skipped
+            })
+            .filter_map(|((_, deadline), _)| deadline.or(overall_deadline))
+            .min();
+        let next_deadline = match next_deadline {
+            Some(data) => data,
+            None => break,
+        };
+
+        // Held windows are looked at again at least every `retry_interval`, as they may become ready without any window event
+        let mut interval = wait_policy.get_interval(attempts);
+        if not_ready_reasons.iter().any(|reason| reason.is_some()) {
+            interval = interval.min(wait_policy.initial_interval);
+        }
+        let wait = interval.min(next_deadline.saturating_duration_since(now));
+        window_system.wait_for_window_event(wait);
+    }
+
+    Ok(LoadReport {
+        name: window_data.name.clone(),
+        entries: entry_reports,
+        closed_windows,
+        minimized_windows,
+    })
+}
+
+/// Launches an entry's application, recording whether it was launched in the entry's report
+fn launch_entry(
+    window_system: &dyn WindowSystem,
+    window: &WindowDataEntry,
+    entry_report: &mut EntryReport,
+) {
+    let (program, args) = match window.launch_command.as_deref() {
+        Some([program, args @ ..]) => (program, args),
+        _ => (&window.application_path, window.application_args.as_slice()),
+    };
+    match window_system.launch_application(program, args, window.working_directory.as_deref()) {
+        Ok(_) => entry_report.status = EntryStatus::Launched,
+        Err(error) => {
+            if verbose() {
+                printwarning!("{:?}", error);
+            }
+            entry_report.status = EntryStatus::LaunchFailed;
+            entry_report.message = Some(format!("{:#}", error));
+        }
+    }
+}
+
+/// Checks whether fewer than `launch_concurrency` launched applications are being waited for, so another can be launched
+fn can_launch(
+    settings: &LoadSettings,
+    entry_reports: &[EntryReport],
+    windows_to_retry: &[bool],
+) -> bool {
+    let waiting_count = entry_reports
+        .iter()
+        .zip(windows_to_retry)
+        .filter(|(entry_report, pending)| **pending && entry_report.status == EntryStatus::Launched)
+        .count();
+    settings.launch_concurrency == 0 || waiting_count < settings.launch_concurrency
+}
+
+/// Gets when to stop waiting for an entry's window
+///
+/// Each entry is waited for from when its application was launched (or from now, if it was not launched), so slow launches do not eat into the wait for later entries
+fn get_entry_deadline(
+    window_system: &dyn WindowSystem,
+    window: &WindowDataEntry,
+    entry_report: &EntryReport,
+    wait_policy: &WaitPolicy,
+    overall_deadline: Option<Instant>,
+) -> Instant {
+    let timeout = match window.timeout {
+        Some(timeout) => Duration::from_millis(timeout as u64),
+        None => wait_policy.entry_timeout,
+    };
+    let deadline = if entry_report.status == EntryStatus::LaunchFailed {
+        // There is no window to wait for, but one that is already opening can still be placed on the next look
+        window_system.now()
+    } else {
+        window_system.now() + timeout
+    };
+
+    match overall_deadline {
+        Some(overall_deadline) => deadline.min(overall_deadline),
+        None => deadline,
+    }
+}
+
+/// Moves a window to where an entry says it should be
+///
+/// # Arguments
+///
+/// * `window_system` - The window system the window belongs to
+/// * `window_id` - The window to move
+/// * `window` - The entry the window was matched to
+/// * `position` - The position to move the window to
+/// * `size` - The size to give the window
+///
+/// # Errors
+///
+/// Returns an error if any step of moving the window failed
+fn place_window(
+    window_system: &dyn WindowSystem,
+    window_id: WindowId,
+    window: &WindowDataEntry,
+    position: &WindowPosition,
+    size: &WindowSize,
+) -> Result<()> {
+    if let Some(workspace) = &window.workspace {
+        window_system.move_to_workspace(window_id, workspace)?;
+    }
+
+    // Restore window to visible position
+    window_system.restore(window_id)?;
+    // Un-maximize window so it can be moved properly
+    if window_system.is_maximized(window_id)? {
+        window_system.restore(window_id)?;
+    }
+
+    window_system.reposition_and_resize(window_id, position, size)?;
+
+    if window.maximised {
+        window_system.maximize(window_id)?;
+    }
+    if window.minimized {
+        window_system.minimize(window_id)?;
+    }
+
+    Ok(())
+}
+
+/// Records the outcome of verifying a window's placement in its entry's report, warning if the application refused the requested size
+///
+/// # Arguments
+///
+/// * `settings` - How the arrangement is being loaded
+/// * `entry_report` - The report of the entry the window was placed for
+/// * `window` - The entry the window was placed for
+/// * `placement` - Where the window was placed
+/// * `result` - The final drift of the window, or why it could not be verified
+fn report_verification(
+    settings: &LoadSettings,
+    entry_report: &mut EntryReport,
+    window: &WindowDataEntry,
+    placement: &Placement,
+    result: Result<PlacementDrift>,
+) {
+    let drift = match result {
+        Ok(data) => data,
+        Err(error) => {
+            if verbose() {
+                printwarning!("{:?}", error);
+            }
+            entry_report.message = Some(format!("could not verify placement: {:#}", error));
+            return;
+        }
+    };
+    entry_report.drift = Some(drift);
+
+    let tolerance = settings.verify_tolerance;
+    if !drift.size_within(tolerance) {
+        let application_name = Path::new(&window.application_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| window.application_path.clone());
+        let actual_width = placement.size.width + drift.width;
+        let actual_height = placement.size.height + drift.height;
+        printwarning!(
+            "{} refused the requested size {}x{} (it stayed at {}x{})",
+            application_name,
+            placement.size.width,
+            placement.size.height,
+            actual_width,
+            actual_height
+        );
+        entry_report.message = Some(format!(
+            "refused size {}x{} (stayed at {}x{})",
+            placement.size.width, placement.size.height, actual_width, actual_height
+        ));
+    } else if !drift.position_within(tolerance) {
+        entry_report.message = Some(String::from("window moved away from where it was placed"));
+    }
+}
+
+/// Prints a load report as a table, or as JSON
+///
+/// # Errors
+///
+/// Returns an error if the report could not be serialized as JSON
+pub fn print_load_report(report: &LoadReport, report_format: ReportFormat) -> Result<()> {
+    if report_format == ReportFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(report)
+                .with_context(|| "error serializing load report as JSON")?
+        );
+        return Ok(());
+    }
+
+    let path_width = report
+        .entries
+        .iter()
+        .map(|entry| entry.application_path.len())
+        .chain(["APPLICATION".len()])
+        .max()
+        .unwrap_or_default();
+
+    println!(
+        "{:<4} {:<width$}  {:<17}  {:<8}  {:<16}  MESSAGE",
+        "#",
+        "APPLICATION",
+        "STATUS",
+        "ATTEMPTS",
+        "DRIFT",
+        width = path_width
+    );
+    for entry in &report.entries {
+        let drift = match &entry.drift {
+            Some(drift) => drift.describe(),
+            None => String::from("-"),
+        };
+        let line = format!(
+            "{:<4} {:<width$}  {:<17}  {:<8}  {:<16}  {}",
+            entry.index,
+            entry.application_path,
+            entry.status.as_str(),
+            entry.attempts,
+            drift,
+            entry.message.as_deref().unwrap_or_default(),
+            width = path_width
+        );
+        println!("{}", line.trim_end());
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::ConfigData,
+        data::{ArrangementSettings, MonitorData, MonitorPlacement, ScreenRect, WindowReadiness},
+        platform::{FakeWindow, FakeWindowSystem},
+    };
+
+    const EDITOR: &str = "/usr/bin/editor";
+    const TERMINAL: &str = "/usr/bin/terminal";
+    const OTHER: &str = "/usr/bin/other";
+
+    fn position(left: i32, top: i32) -> WindowPosition {
+        WindowPosition { top, left }
+    }
+
+    fn size(width: i32, height: i32) -> WindowSize {
+        WindowSize { width, height }
+    }
+
+    fn entry(application_path: &str, left: i32, top: i32) -> WindowDataEntry {
+        WindowDataEntry {
+            application_path: application_path.to_string(),
+            application_args: Vec::new(),
+            launch_command: None,
+            working_directory: None,
+            launch: true,
+            reposition: true,
+            position: position(left, top),
+            size: size(800, 600),
+            minimized: false,
+            maximised: false,
+            window_title: None,
+            title_pattern: None,
+            window_class: None,
+            instance: None,
+            workspace: None,
+            monitor: None,
+            timeout: None,
+            ready_when: None,
+        }
+    }
+
+    fn arrangement(entries: Vec<WindowDataEntry>) -> WindowData {
+        WindowData {
+            version: 0,
+            name: String::from("test"),
+            description: None,
+            tags: Vec::new(),
+            created_at: None,
+            updated_at: None,
+            created_on_host: None,
+            windough_version: None,
+            data: entries,
+            monitors: Vec::new(),
+            settings: None,
+        }
+    }
+
+    fn window(application_path: &str) -> FakeWindow {
+        FakeWindow::new(application_path, "Window", position(0, 0), size(400, 300))
+    }
+
+    /// Gets the settings to load with, from a configuration given as JSON (with every other value left at its default)
+    fn settings(config: &str, other_windows: Option<OtherWindows>) -> LoadSettings {
+        let config: ConfigData = serde_json::from_str(config).unwrap();
+        let arrangement_settings = ArrangementSettings::default();
+        let wait_policy =
+            WaitPolicy::from_config(&config, &arrangement_settings, None, None, None, None);
+        LoadSettings::from_config(
+            &config,
+            &arrangement_settings,
+            wait_policy,
+            other_windows,
+            None,
+        )
+    }
+
+    #[test]
+    fn places_window_that_opens_after_launch() {
+        let window_system = FakeWindowSystem::new();
+        window_system.on_launch(EDITOR, Duration::from_secs(2), window(EDITOR));
+        let window_data = arrangement(vec![entry(EDITOR, 100, 50)]);
+
+        let report = arrange_windows(&window_system, &window_data, &settings("{}", None)).unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::Placed);
+        assert_eq!(report.failed_count(), 0);
+        assert_eq!(window_system.launched().len(), 1);
+        assert_eq!(window_system.launched()[0].application_path, EDITOR);
+        assert!(window_system.elapsed() >= Duration::from_secs(2));
+
+        let id = window_system.get_open_windows().unwrap()[0];
+        let placed = window_system.window(id).unwrap();
+        assert_eq!(placed.position, position(100, 50));
+        assert_eq!(placed.size, size(800, 600));
+    }
+
+    #[test]
+    fn places_already_open_window_without_launching() {
+        let window_system = FakeWindowSystem::new();
+        let id = window_system.open_window(window(EDITOR));
+        let window_data = arrangement(vec![entry(EDITOR, 100, 50)]);
+
+        let report = arrange_windows(&window_system, &window_data, &settings("{}", None)).unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::Placed);
+        assert!(window_system.launched().is_empty());
+        assert_eq!(
+            window_system.window(id).unwrap().position,
+            position(100, 50)
+        );
+    }
+
+    #[test]
+    fn places_windows_of_same_application_by_title() {
+        let window_system = FakeWindowSystem::new();
+        let notes = window_system.open_window(FakeWindow::new(
+            EDITOR,
+            "notes.txt",
+            position(0, 0),
+            size(400, 300),
+        ));
+        let todo = window_system.open_window(FakeWindow::new(
+            EDITOR,
+            "todo.txt",
+            position(0, 0),
+            size(400, 300),
+        ));
+        let mut todo_entry = entry(EDITOR, 0, 0);
+        todo_entry.window_title = Some(String::from("todo.txt"));
+        let mut notes_entry = entry(EDITOR, 800, 0);
+        notes_entry.window_title = Some(String::from("notes.txt"));
+        let window_data = arrangement(vec![todo_entry, notes_entry]);
+
+        arrange_windows(&window_system, &window_data, &settings("{}", None)).unwrap();
+
+        assert!(window_system.launched().is_empty());
+        assert_eq!(window_system.window(todo).unwrap().position, position(0, 0));
+        assert_eq!(
+            window_system.window(notes).unwrap().position,
+            position(800, 0)
+        );
+    }
+
+    #[test]
+    fn gives_up_when_window_does_not_appear_in_time() {
+        let window_system = FakeWindowSystem::new();
+        window_system.on_launch(EDITOR, Duration::from_secs(60), window(EDITOR));
+        let window_data = arrangement(vec![entry(EDITOR, 100, 50)]);
+
+        let report = arrange_windows(
+            &window_system,
+            &window_data,
+            &settings(r#"{"wait_timeout": 5000}"#, None),
+        )
+        .unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::NotFound);
+        assert_eq!(report.failed_count(), 1);
+        // Waiting stops at the deadline, rather than after another full interval
+        assert!(window_system.elapsed() >= Duration::from_secs(5));
+        assert!(window_system.elapsed() < Duration::from_secs(6));
+    }
+
+    #[test]
+    fn holds_window_until_title_matches() {
+        let window_system = FakeWindowSystem::new();
+        let id = window_system.open_window(FakeWindow::new(
+            EDITOR,
+            "Loading...",
+            position(0, 0),
+            size(400, 300),
+        ));
+        window_system.retitle_window_after(Duration::from_secs(3), id, "notes.txt - Editor");
+        let mut editor = entry(EDITOR, 100, 50);
+        editor.ready_when = Some(WindowReadiness {
+            title_pattern: Some(String::from("notes")),
+            ..WindowReadiness::default()
+        });
+        let window_data = arrangement(vec![editor]);
+
+        let report = arrange_windows(
+            &window_system,
+            &window_data,
+            &settings(r#"{"wait_timeout": 10000}"#, None),
+        )
+        .unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::Placed);
+        assert!(window_system.elapsed() >= Duration::from_secs(3));
+        assert_eq!(
+            window_system.window(id).unwrap().position,
+            position(100, 50)
+        );
+    }
+
+    #[test]
+    fn holds_window_until_size_is_stable() {
+        let window_system = FakeWindowSystem::new();
+        let id = window_system.open_window(window(EDITOR));
+        // The application resizes its window twice while it starts
+        window_system.move_window_after(Duration::from_secs(1), id, position(0, 0), size(600, 400));
+        window_system.move_window_after(Duration::from_secs(2), id, position(0, 0), size(900, 700));
+        let mut editor = entry(EDITOR, 100, 50);
+        editor.ready_when = Some(WindowReadiness {
+            stable_for: Some(1500),
+            ..WindowReadiness::default()
+        });
+        let window_data = arrangement(vec![editor]);
+
+        let report = arrange_windows(
+            &window_system,
+            &window_data,
+            &settings(r#"{"wait_timeout": 10000}"#, None),
+        )
+        .unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::Placed);
+        // Placed no sooner than 1.5s after the last resize, so the resizes did not undo it
+        assert!(window_system.elapsed() >= Duration::from_millis(3500));
+        let placed = window_system.window(id).unwrap();
+        assert_eq!(placed.position, position(100, 50));
+        assert_eq!(placed.size, size(800, 600));
+    }
+
+    #[test]
+    fn reports_window_that_never_becomes_ready() {
+        let window_system = FakeWindowSystem::new();
+        let id = window_system.open_window(FakeWindow::new(
+            EDITOR,
+            "Loading...",
+            position(0, 0),
+            size(400, 300),
+        ));
+        let mut editor = entry(EDITOR, 100, 50);
+        editor.ready_when = Some(WindowReadiness {
+            title_pattern: Some(String::from("notes")),
+            ..WindowReadiness::default()
+        });
+        let window_data = arrangement(vec![editor]);
+
+        let report = arrange_windows(
+            &window_system,
+            &window_data,
+            &settings(r#"{"wait_timeout": 5000}"#, None),
+        )
+        .unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::NotReady);
+        assert_eq!(
+            report.entries[0].message.as_deref(),
+            Some("window did not become ready: title 'Loading...' does not match 'notes'")
+        );
+        assert_eq!(report.failed_count(), 1);
+        assert!(window_system.elapsed() < Duration::from_secs(6));
+        assert_eq!(window_system.window(id).unwrap().position, position(0, 0));
+    }
+
+    #[test]
+    fn whole_load_timeout_stops_entries_still_to_be_launched() {
+        let window_system = FakeWindowSystem::new();
+        window_system.on_launch(EDITOR, Duration::from_secs(60), window(EDITOR));
+        window_system.on_launch(TERMINAL, Duration::from_secs(1), window(TERMINAL));
+        let window_data = arrangement(vec![entry(EDITOR, 0, 0), entry(TERMINAL, 800, 0)]);
+        let mut settings = settings(r#"{"wait_timeout": 30000, "launch_concurrency": 1}"#, None);
+        settings.wait_policy.overall_timeout = Some(Duration::from_secs(10));
+
+        let report = arrange_windows(&window_system, &window_data, &settings).unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::NotFound);
+        assert_eq!(report.entries[1].status, EntryStatus::NotFound);
+        assert_eq!(
+            report.entries[1].message.as_deref(),
+            Some("the load timed out before it could be launched")
+        );
+        assert_eq!(window_system.launched().len(), 1);
+    }
+
+    #[test]
+    fn reports_launch_failure_without_waiting() {
+        let window_system = FakeWindowSystem::new();
+        // Nothing is scripted for the editor, so launching it fails
+        window_system.on_launch(TERMINAL, Duration::from_secs(1), window(TERMINAL));
+        let window_data = arrangement(vec![entry(EDITOR, 0, 0), entry(TERMINAL, 800, 0)]);
+
+        let report = arrange_windows(
+            &window_system,
+            &window_data,
+            &settings(r#"{"wait_timeout": 30000}"#, None),
+        )
+        .unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::LaunchFailed);
+        assert!(report.entries[0]
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("Failed to launch application"));
+        assert_eq!(report.entries[1].status, EntryStatus::Placed);
+        assert_eq!(report.failed_count(), 1);
+        // The failed launch is not waited for
+        assert!(window_system.elapsed() < Duration::from_secs(30));
+    }
+
+    #[test]
+    fn closes_other_windows() {
+        let window_system = FakeWindowSystem::new();
+        let editor = window_system.open_window(window(EDITOR));
+        let other = window_system.open_window(window(OTHER));
+        let window_data = arrangement(vec![entry(EDITOR, 0, 0)]);
+
+        let report = arrange_windows(
+            &window_system,
+            &window_data,
+            &settings("{}", Some(OtherWindows::Close)),
+        )
+        .unwrap();
+
+        assert_eq!(report.closed_windows, vec![other]);
+        assert_eq!(window_system.closed(), vec![other]);
+        assert!(window_system.window(other).is_none());
+        assert!(window_system.window(editor).is_some());
+    }
+
+    #[test]
+    fn minimizes_other_windows() {
+        let window_system = FakeWindowSystem::new();
+        let editor = window_system.open_window(window(EDITOR));
+        let other = window_system.open_window(window(OTHER));
+        let window_data = arrangement(vec![entry(EDITOR, 0, 0)]);
+
+        let report = arrange_windows(
+            &window_system,
+            &window_data,
+            &settings("{}", Some(OtherWindows::Minimize)),
+        )
+        .unwrap();
+
+        assert_eq!(report.minimized_windows, vec![other]);
+        assert!(report.closed_windows.is_empty());
+        assert!(window_system.window(other).unwrap().minimized);
+        assert!(!window_system.window(editor).unwrap().minimized);
+    }
+
+    #[test]
+    fn leaves_excluded_windows_alone() {
+        let window_system = FakeWindowSystem::new();
+        let other = window_system.open_window(window(OTHER));
+        let window_data = arrangement(vec![]);
+
+        let report = arrange_windows(
+            &window_system,
+            &window_data,
+            &settings(
+                r#"{"exclude_windows": [{"exe": "/usr/bin/other"}]}"#,
+                Some(OtherWindows::Close),
+            ),
+        )
+        .unwrap();
+
+        assert!(report.closed_windows.is_empty());
+        assert!(window_system.window(other).is_some());
+    }
+
+    #[test]
+    fn moves_drifting_window_back() {
+        let window_system = FakeWindowSystem::new();
+        let id = window_system.open_window(window(EDITOR));
+        // The application moves its window again just after it is placed
+        window_system.move_window_after(
+            Duration::from_millis(100),
+            id,
+            position(300, 300),
+            size(800, 600),
+        );
+        let window_data = arrangement(vec![entry(EDITOR, 100, 50)]);
+
+        let report = arrange_windows(&window_system, &window_data, &settings("{}", None)).unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::Placed);
+        assert_eq!(
+            window_system.window(id).unwrap().position,
+            position(100, 50)
+        );
+    }
+
+    #[test]
+    fn reports_refused_size() {
+        let window_system = FakeWindowSystem::new();
+        let mut editor = window(EDITOR);
+        editor.min_size = Some(size(1000, 700));
+        let id = window_system.open_window(editor);
+        let window_data = arrangement(vec![entry(EDITOR, 100, 50)]);
+
+        let report = arrange_windows(&window_system, &window_data, &settings("{}", None)).unwrap();
+
+        assert_eq!(window_system.window(id).unwrap().size, size(1000, 700));
+        assert_eq!(
+            report.entries[0].message.as_deref(),
+            Some("refused size 800x600 (stayed at 1000x700)")
+        );
+    }
+
+    #[test]
+    fn launches_launch_command_in_working_directory() {
+        let window_system = FakeWindowSystem::new();
+        window_system.on_launch("/usr/bin/flatpak", Duration::from_secs(1), window(EDITOR));
+        let mut editor = entry(EDITOR, 100, 50);
+        editor.launch_command = Some(vec![
+            String::from("/usr/bin/flatpak"),
+            String::from("run"),
+            String::from("org.example.Editor"),
+        ]);
+        editor.working_directory = Some(String::from("/home/user/project"));
+        let window_data = arrangement(vec![editor]);
+
+        let report = arrange_windows(&window_system, &window_data, &settings("{}", None)).unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::Placed);
+        let launched = window_system.launched();
+        assert_eq!(launched.len(), 1);
+        assert_eq!(launched[0].application_path, "/usr/bin/flatpak");
+        assert_eq!(launched[0].application_args, ["run", "org.example.Editor"]);
+        assert_eq!(
+            launched[0].working_directory.as_deref(),
+            Some("/home/user/project")
+        );
+    }
+
+    #[test]
+    fn remaps_window_onto_changed_monitor() {
+        let monitor = |id: &str, width: i32, height: i32| {
+            let bounds = ScreenRect {
+                left: 0,
+                top: 0,
+                width,
+                height,
+            };
+            MonitorData {
+                id: id.to_string(),
+                bounds,
+                work_area: bounds,
+                scale: 1.0,
+                primary: true,
+            }
+        };
+        let window_system = FakeWindowSystem::new();
+        window_system.set_monitors(vec![monitor("DP-2", 3840, 2160)]);
+        let id = window_system.open_window(window(EDITOR));
+        let mut editor = entry(EDITOR, 100, 50);
+        editor.monitor = Some(MonitorPlacement {
+            id: String::from("DP-1"),
+            position: position(100, 50),
+            size: size(800, 600),
+        });
+        let mut window_data = arrangement(vec![editor]);
+        window_data.monitors = vec![monitor("DP-1", 1920, 1080)];
+
+        arrange_windows(&window_system, &window_data, &settings("{}", None)).unwrap();
+
+        let placed = window_system.window(id).unwrap();
+        assert_eq!(placed.position, position(200, 100));
+        assert_eq!(placed.size, size(1600, 1200));
+    }
+
+    #[test]
+    fn skips_entry_that_is_not_running_and_not_launched() {
+        let window_system = FakeWindowSystem::new();
+        let mut editor = entry(EDITOR, 100, 50);
+        editor.launch = false;
+        let window_data = arrangement(vec![editor]);
+
+        let report = arrange_windows(
+            &window_system,
+            &window_data,
+            &settings(r#"{"wait_timeout": 30000}"#, None),
+        )
+        .unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::Skipped);
+        assert_eq!(report.failed_count(), 0);
+        assert!(window_system.launched().is_empty());
+        assert_eq!(window_system.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn places_skipped_entry_if_its_window_opens_during_load() {
+        let window_system = FakeWindowSystem::new();
+        window_system.on_launch(TERMINAL, Duration::from_secs(3), window(TERMINAL));
+        let editor_window = window_system.open_window_after(Duration::from_secs(1), window(EDITOR));
+        let mut editor = entry(EDITOR, 100, 50);
+        editor.launch = false;
+        let window_data = arrangement(vec![editor, entry(TERMINAL, 800, 0)]);
+
+        let report = arrange_windows(&window_system, &window_data, &settings("{}", None)).unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::Placed);
+        assert_eq!(report.entries[1].status, EntryStatus::Placed);
+        assert_eq!(
+            window_system.window(editor_window).unwrap().position,
+            position(100, 50)
+        );
+    }
+
+    #[test]
+    fn places_minimized_maximized_window() {
+        let window_system = FakeWindowSystem::new();
+        let mut editor = window(EDITOR);
+        editor.minimized = true;
+        editor.maximized = true;
+        let id = window_system.open_window(editor);
+        let window_data = arrangement(vec![entry(EDITOR, 100, 50)]);
+
+        let report = arrange_windows(&window_system, &window_data, &settings("{}", None)).unwrap();
+
+        assert_eq!(report.entries[0].status, EntryStatus::Placed);
+        let placed = window_system.window(id).unwrap();
+        assert!(!placed.minimized);
+        assert!(!placed.maximized);
+        assert_eq!(placed.position, position(100, 50));
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{
+    data::{
+        get_data_dir, get_version, migrate as migrate_value, write_window_data, WindowData,
+        CURRENT_VERSION,
+    },
+    printinfo, printwarning,
+    utils::{record_revision, resource_exists, ResourceType},
+    verbose, CONFIG,
+};
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::{
+    fs::{self, DirEntry},
+    path::Path,
+};
+
+pub fn migrate() -> Result<()> {
+    let path = get_data_dir();
+    if !resource_exists(&path, ResourceType::Dir, false)? {
+        if verbose() {
+            printinfo!("data directory does not exist");
+        }
+
+        return Ok(());
+    }
+
+    let mut failed_count = 0;
+
+    for entry in fs::read_dir(path).with_context(|| "Failed to read directory")? {
+        let result = entry
+            .with_context(|| "failed to get item from directory")
+            .and_then(migrate_item);
+        match result {
+            Ok(Some((name, from_version))) => {
+                println!("{} (version {} -> {})", name, from_version, CURRENT_VERSION);
+            }
+            Ok(None) => (),
+            Err(error) => {
+                printwarning!("{:#}", error);
+                failed_count += 1;
+            }
+        }
+    }
+
+    if failed_count > 0 {
+        bail!("Failed to migrate {} arrangement(s)", failed_count);
+    }
+
+    Ok(())
+}
+
+/// Rewrites an arrangement file at the current schema version, returning its name and previous version if it was migrated
+fn migrate_item(item: DirEntry) -> Result<Option<(String, u64)>> {
+    let path = item.path();
+    if !item
+        .metadata()
+        .with_context(|| "failed to get item metadata")?
+        .is_file()
+        || path.extension().and_then(|extension| extension.to_str()) != Some("json")
+    {
+        return Ok(None);
+    }
+
+    let display_name = get_display_name(&path);
+    let json_string = fs::read_to_string(&path)
+        .with_context(|| format!("{}: error reading data from file", display_name))?;
+    let value: Value = serde_json::from_str(&json_string)
+        .with_context(|| format!("{}: error parsing file contents as JSON", display_name))?;
+
+    let from_version = get_version(&value).with_context(|| display_name.clone())?;
+    if from_version == CURRENT_VERSION {
+        if verbose() {
+            printinfo!("{} is already at version {}", display_name, CURRENT_VERSION);
+        }
+        return Ok(None);
+    }
+
+    let value = migrate_value(value).with_context(|| display_name.clone())?;
+    let window_data: WindowData = serde_json::from_value(value)
+        .with_context(|| format!("{}: error reading arrangement data", display_name))?;
+    if window_data.name != display_name {
+        bail!(
+            "{}: 'name' property in file does not match expected name",
+            display_name
+        );
+    }
+
+    write_window_data(&window_data, CONFIG.backup_count).with_context(|| display_name.clone())?;
+    record_revision(window_data, "migrate");
+
+    Ok(Some((display_name, from_version)))
+}
+
+fn get_display_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
@@ -1,13 +1,33 @@
 mod clear;
+mod config;
+mod copy;
+mod diff;
+mod edit;
+mod history;
 mod list;
 mod load;
+mod migrate;
 mod open_dir;
 mod remove;
+mod rename;
+mod restore;
 mod save;
+mod show;
+mod undo;
 
 pub use clear::clear;
-pub use list::list;
-pub use load::load;
+pub use config::{config_get, config_list, config_set, config_unset};
+pub use copy::copy;
+pub use diff::{diff, DiffTarget};
+pub use edit::{edit, EditOperation, EntryFields, EntrySelector};
+pub use history::history;
+pub use list::{list, ListOptions, ListSort};
+pub use load::{load, LoadOptions};
+pub use migrate::migrate;
 pub use open_dir::open_dir;
 pub use remove::remove;
-pub use save::save;
+pub use rename::rename;
+pub use restore::restore;
+pub use save::{save, SaveOptions};
+pub use show::{show, ShowFormat};
+pub use undo::undo;
@@ -3,16 +3,7 @@ use crate::{
     PROJECT_DIRS,
 };
 use anyhow::{bail, Context, Result};
-use std::{
-    ffi::OsString,
-    os::windows::ffi::OsStrExt,
-    path::Path,
-    ptr::{null, null_mut},
-};
-use winapi::{
-    shared::basetsd::INT_PTR,
-    um::{errhandlingapi::GetLastError, shellapi::ShellExecuteW, winuser::SW_NORMAL},
-};
+use std::path::Path;
 
 pub fn open_dir(root: bool, data: bool, config: bool) -> Result<()> {
     let path: String;
@@ -37,6 +28,21 @@ pub fn open_dir(root: bool, data: bool, config: bool) -> Result<()> {
 
     resource_exists(Path::new(&path), ResourceType::Dir, true)?;
 
+    open_in_file_manager(path)
+}
+
+#[cfg(windows)]
+fn open_in_file_manager(path: String) -> Result<()> {
+    use std::{
+        ffi::OsString,
+        os::windows::ffi::OsStrExt,
+        ptr::{null, null_mut},
+    };
+    use winapi::{
+        shared::basetsd::INT_PTR,
+        um::{errhandlingapi::GetLastError, shellapi::ShellExecuteW, winuser::SW_NORMAL},
+    };
+
     let mut path_osstring = OsString::from(path);
     path_osstring.push(OsString::from("\0"));
     let path_wide: Vec<u16> = path_osstring.encode_wide().collect();
@@ -62,3 +68,18 @@ pub fn open_dir(root: bool, data: bool, config: bool) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(not(windows))]
+fn open_in_file_manager(path: String) -> Result<()> {
+    use std::process::{Command, Stdio};
+
+    Command::new("xdg-open")
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| "Failed to run xdg-open")?;
+
+    Ok(())
+}
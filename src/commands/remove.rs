@@ -1,24 +1,83 @@
 use crate::{
-    printinfo,
-    utils::{resource_exists, validate_name, ResourceType},
-    verbose, PROJECT_DIRS,
+    data::{
+        get_window_data_path, list_window_data_names, remove_backups, remove_last_loaded,
+        remove_revisions,
+    },
+    printinfo, printwarning,
+    utils::{confirm, validate_name},
+    verbose,
 };
-use anyhow::{Context, Result};
-use std::fs;
+use anyhow::{bail, Context, Result};
+use globset::Glob;
+use std::{collections::BTreeSet, fs};
 
-pub fn remove(name: String) -> Result<()> {
-    validate_name(&name)?;
+/// Removes the arrangements with the given names, or whose names match the given glob patterns (e.g. `work-*`)
+///
+/// Names that do not exist are skipped. If more than one arrangement is to be removed, the user is asked first (unless `force` is set)
+///
+/// Each arrangement's backups and the time it was last loaded are removed with it, and so is its revision history unless `keep_history` is set, so that an arrangement saved later with the same name does not inherit them
+pub fn remove(patterns: Vec<String>, dry_run: bool, force: bool, keep_history: bool) -> Result<()> {
+    let names = list_window_data_names()?;
+    let mut matched: BTreeSet<String> = BTreeSet::new();
 
-    let file_path = PROJECT_DIRS.data_dir().join(format!("{}.json", name));
-    if !resource_exists(file_path.as_path(), ResourceType::File, false)? {
-        if verbose() {
-            printinfo!("file does not exist");
+    for pattern in &patterns {
+        let found: Vec<&String> = if pattern.contains(['*', '?', '[', '{']) {
+            let glob = Glob::new(pattern)
+                .with_context(|| format!("Invalid pattern '{}'", pattern))?
+                .compile_matcher();
+            names.iter().filter(|name| glob.is_match(name)).collect()
+        } else {
+            validate_name(pattern)?;
+            names.iter().filter(|name| *name == pattern).collect()
+        };
+
+        if found.is_empty() && verbose() {
+            printinfo!("no arrangement matches '{}'", pattern);
         }
+        matched.extend(found.into_iter().cloned());
+    }
 
+    if dry_run {
+        for name in &matched {
+            println!("{}", name);
+        }
         return Ok(());
     }
 
-    fs::remove_file(file_path).with_context(|| "Failed to remove file")?;
+    if matched.len() > 1 && !force {
+        let question = format!(
+            "Remove {} arrangements ({})?",
+            matched.len(),
+            matched.iter().cloned().collect::<Vec<String>>().join(", ")
+        );
+        match confirm(&question)? {
+            Some(true) => (),
+            Some(false) => bail!("No arrangements were removed"),
+            None => bail!(
+                "{} arrangements match - use --force to remove them without asking",
+                matched.len()
+            ),
+        }
+    }
+
+    for name in &matched {
+        fs::remove_file(get_window_data_path(name))
+            .with_context(|| format!("Failed to remove '{}'", name))?;
+        remove_backups(name).with_context(|| format!("Failed to remove backups of '{}'", name))?;
+        if !keep_history {
+            remove_revisions(name)
+                .with_context(|| format!("Failed to remove revision history of '{}'", name))?;
+        }
+        if let Err(error) = remove_last_loaded(name) {
+            printwarning!(
+                "{:#}",
+                error.context(format!("failed to forget when '{}' was last loaded", name))
+            );
+        }
+        if verbose() {
+            printinfo!("removed '{}'", name);
+        }
+    }
 
     Ok(())
 }
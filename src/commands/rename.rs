@@ -0,0 +1,46 @@
+use crate::{
+    data::{
+        get_window_data_path, move_backups, move_revisions, read_window_data, rename_last_loaded,
+        write_window_data,
+    },
+    printwarning,
+    utils::{check_overwrite, record_revision, validate_name},
+};
+use anyhow::{ensure, Context, Result};
+use chrono::Utc;
+use std::fs;
+
+/// Renames an arrangement, along with its backups, when it was last loaded and its revision history
+///
+/// An arrangement that already has the new name is replaced, along with its backups and history
+pub fn rename(old_name: String, new_name: String, force: bool) -> Result<()> {
+    validate_name(&old_name)?;
+    validate_name(&new_name)?;
+    ensure!(
+        old_name != new_name,
+        "The new name is the same as the old one"
+    );
+
+    let mut data = read_window_data(&old_name)
+        .with_context(|| format!("Failed to read arrangement '{}'", old_name))?;
+    check_overwrite(&new_name, force)?;
+
+    data.name = new_name.clone();
+    data.updated_at = Some(Utc::now());
+    // The arrangement is written under its new name before the old file is removed, so it is never lost. The arrangement it replaces is not backed up, as its backups are replaced too
+    write_window_data(&data, 0).with_context(|| "Failed to write arrangement")?;
+    move_backups(&old_name, &new_name).with_context(|| "Failed to move backups")?;
+    move_revisions(&old_name, &new_name)?;
+    fs::remove_file(get_window_data_path(&old_name))
+        .with_context(|| format!("Failed to remove '{}' after copying it", old_name))?;
+
+    if let Err(error) = rename_last_loaded(&old_name, &new_name) {
+        printwarning!(
+            "{:#}",
+            error.context("failed to move when the arrangement was last loaded")
+        );
+    }
+    record_revision(data, &format!("rename from {}", old_name));
+
+    Ok(())
+}
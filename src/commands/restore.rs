@@ -0,0 +1,25 @@
+use crate::{
+    data::{read_revision, write_window_data},
+    printinfo,
+    utils::{record_revision, validate_name},
+    CONFIG,
+};
+use anyhow::{Context, Result};
+use chrono::Utc;
+
+/// Replaces an arrangement with one of its revisions (which also works for arrangements removed with `--keep-history`)
+pub fn restore(name: String, number: usize) -> Result<()> {
+    validate_name(&name)?;
+
+    let mut data = read_revision(&name, number)?.window_data;
+    // Revisions from before the arrangement was renamed have its old name
+    data.name = name.clone();
+    data.updated_at = Some(Utc::now());
+
+    write_window_data(&data, CONFIG.backup_count).with_context(|| "Failed to write arrangement")?;
+    record_revision(data, &format!("restore {}", number));
+
+    printinfo!("restored '{}' to revision {}", name, number);
+
+    Ok(())
+}
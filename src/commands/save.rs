@@ -1,106 +1,81 @@
 use crate::{
-    data::{WindowData, WindowDataEntry, WindowPosition, WindowSize},
+    config::WindowFilter,
+    data::{read_window_data, write_window_data, WindowData},
+    platform::WindowSystem,
+    printwarning,
     utils::{
-        get_module_paths_from_windows, get_open_windows, resource_exists, validate_name,
-        ResourceType,
-    },
-    PROJECT_DIRS,
-};
-use anyhow::{bail, Context, Result};
-use std::fs;
-use winapi::{
-    shared::windef::{HWND, RECT},
-    um::{
-        errhandlingapi::GetLastError,
-        winuser::{GetWindowRect, IsIconic, IsZoomed, ShowWindow, SW_MINIMIZE, SW_RESTORE},
+        apply_window_rules, capture_arrangement, check_overwrite, record_revision, validate_name,
     },
+    CONFIG,
 };
+use anyhow::{Context, Result};
+use chrono::Utc;
+
+/// The command-line options of `save`
+pub struct SaveOptions {
+    pub include: Vec<WindowFilter>,
+    pub exclude: Vec<WindowFilter>,
+    /// Replaces the arrangement's description, if given
+    pub description: Option<String>,
+    /// Replaces the arrangement's tags, if any are given
+    pub tags: Vec<String>,
+    /// Overwrites an existing arrangement without asking
+    pub force: bool,
+}
 
-pub fn save(name: String) -> Result<()> {
+pub fn save(window_system: &dyn WindowSystem, name: String, options: SaveOptions) -> Result<()> {
     validate_name(&name)?;
 
-    let open_windows = get_open_windows()?;
-    let module_paths = get_module_paths_from_windows(&open_windows);
-
-    let mut window_data = Vec::new();
-
-    for i in 0..(open_windows.len()) {
-        if open_windows[i].is_null() {
-            continue;
-        }
-
-        let module_path = match module_paths[i].clone() {
-            Some(data) => data,
-            None => continue,
-        };
+    let exists = check_overwrite(&name, options.force)?;
 
-        let is_minimized = unsafe { IsIconic(open_windows[i]) == 1 };
-        restore_window(open_windows[i], is_minimized); // For properly checking details when not minimized
-        let is_maximized = unsafe { IsZoomed(open_windows[i]) == 1 };
+    // Filters given on the command line are added to those in the configuration
+    let mut filters = CONFIG.get_window_filters();
+    filters.include.extend(options.include);
+    filters.exclude.extend(options.exclude);
 
-        let mut window_rect = RECT {
-            top: 0,
-            bottom: 0,
-            left: 0,
-            right: 0,
-        };
-        unsafe {
-            if GetWindowRect(open_windows[i], &mut window_rect) == 0 {
-                minimize_window(open_windows[i], is_minimized); // Restore window to its previous (minimized) state
+    let (mut data, _) = capture_arrangement(window_system, name, &filters, true)?;
+    for entry in &mut data.data {
+        apply_window_rules(entry, &CONFIG.rules)?;
+    }
 
-                bail!(
-                    "Failed to get window rect (Win32 error: {})",
-                    GetLastError()
-                );
-            }
+    // Settings and metadata describe the arrangement rather than what was captured, so they are kept when it is saved again
+    if exists {
+        match read_window_data(&data.name) {
+            Ok(existing) => keep_details(&mut data, existing),
+            Err(error) => printwarning!(
+                "{:#}",
+                error.context(
+                    "failed to read the existing arrangement - its settings and metadata will not be kept"
+                )
+            ),
         }
-
-        minimize_window(open_windows[i], is_minimized); // Restore window to its previous (minimized) state
-
-        let window_width = window_rect.right - window_rect.left;
-        let window_height = window_rect.bottom - window_rect.top;
-
-        window_data.push(WindowDataEntry {
-            application_path: module_path,
-            application_args: Vec::new(),
-            launch: true,
-            reposition: true,
-            position: WindowPosition {
-                top: window_rect.top,
-                left: window_rect.left,
-            },
-            size: WindowSize {
-                width: window_width,
-                height: window_height,
-            },
-            minimized: is_minimized,
-            maximised: is_maximized,
-        });
     }
 
-    let data = WindowData {
-        name: name.clone(),
-        data: window_data,
-    };
-
-    let json_string =
-        serde_json::to_string(&data).with_context(|| "Error formatting data as JSON string")?;
-
-    resource_exists(PROJECT_DIRS.data_dir(), ResourceType::Dir, true)?;
-    let file_path = PROJECT_DIRS.data_dir().join(format!("{}.json", name));
+    let now = Utc::now();
+    data.created_at = data.created_at.or(Some(now));
+    data.updated_at = Some(now);
+    if data.created_on_host.is_none() {
+        data.created_on_host = gethostname::gethostname().into_string().ok();
+    }
+    data.windough_version = Some(env!("CARGO_PKG_VERSION").to_string());
+    if let Some(description) = options.description {
+        data.description = Some(description);
+    }
+    if !options.tags.is_empty() {
+        data.tags = options.tags;
+    }
 
-    fs::write(file_path, json_string).with_context(|| "Error writing data to file")?;
+    write_window_data(&data, CONFIG.backup_count).with_context(|| "Failed to write arrangement")?;
+    record_revision(data, "save");
 
     Ok(())
 }
 
-fn restore_window(hwnd: HWND, is_minimized: bool) {
-    if is_minimized {
-        unsafe { ShowWindow(hwnd, SW_RESTORE) };
-    }
-}
-fn minimize_window(hwnd: HWND, is_minimized: bool) {
-    if is_minimized {
-        unsafe { ShowWindow(hwnd, SW_MINIMIZE) };
-    }
+/// Copies the settings and metadata of the arrangement being replaced into its new data
+fn keep_details(data: &mut WindowData, existing: WindowData) {
+    data.settings = existing.settings;
+    data.description = existing.description;
+    data.tags = existing.tags;
+    data.created_at = existing.created_at;
+    data.created_on_host = existing.created_on_host;
 }
@@ -0,0 +1,148 @@
+use crate::{
+    data::{read_window_data, WindowDataEntry},
+    printwarning,
+    utils::{executable_exists, get_application_name, print_table, validate_name},
+};
+use anyhow::{bail, Context, Result};
+
+/// How `show` prints an arrangement
+#[derive(Clone, Copy, PartialEq)]
+pub enum ShowFormat {
+    Table,
+    Json,
+    Toml,
+}
+
+pub fn show(name: String, format: ShowFormat, check: bool) -> Result<()> {
+    validate_name(&name)?;
+
+    let window_data = read_window_data(&name)?;
+
+    let missing: Vec<bool> = window_data
+        .data
+        .iter()
+        .map(|entry| check && !executable_exists(get_executable(entry)))
+        .collect();
+
+    match format {
+        ShowFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&window_data)
+                .with_context(|| "Error formatting arrangement as JSON")?
+        ),
+        ShowFormat::Toml => print!(
+            "{}",
+            toml::to_string_pretty(&window_data)
+                .with_context(|| "Error formatting arrangement as TOML")?
+        ),
+        ShowFormat::Table => {
+            let mut headers = vec![
+                "#",
+                "APPLICATION",
+                "PATH",
+                "ARGS",
+                "LAUNCH",
+                "REPOSITION",
+                "POSITION",
+                "SIZE",
+                "STATE",
+            ];
+            if check {
+                headers.push("CHECK");
+            }
+
+            let rows: Vec<Vec<String>> = window_data
+                .data
+                .iter()
+                .zip(&missing)
+                .enumerate()
+                .map(|(index, (entry, is_missing))| {
+                    let mut row = vec![
+                        index.to_string(),
+                        get_application_name(&entry.application_path),
+                        entry.application_path.clone(),
+                        format_args(&entry.application_args),
+                        format_flag(entry.launch),
+                        format_flag(entry.reposition),
+                        format!("{:+}{:+}", entry.position.left, entry.position.top),
+                        format!("{}x{}", entry.size.width, entry.size.height),
+                        get_state(entry).to_string(),
+                    ];
+                    if check {
+                        row.push(String::from(if *is_missing { "missing" } else { "ok" }));
+                    }
+                    row
+                })
+                .collect();
+
+            println!(
+                "'{}' ({} entries)",
+                window_data.name,
+                window_data.data.len()
+            );
+            print_table(&headers, &rows);
+        }
+    }
+
+    // Missing executables are also warned about on stderr, so they are reported whatever the output format
+    let mut missing_count = 0;
+    for (index, entry) in window_data.data.iter().enumerate() {
+        if missing[index] {
+            printwarning!(
+                "entry {}: executable '{}' does not exist",
+                index,
+                get_executable(entry)
+            );
+            missing_count += 1;
+        }
+    }
+    if missing_count > 0 {
+        bail!(
+            "{} of {} entries have a missing executable",
+            missing_count,
+            window_data.data.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Gets the executable an entry launches (its launch command's program, if it has one)
+fn get_executable(entry: &WindowDataEntry) -> &str {
+    match entry.launch_command.as_deref() {
+        Some([program, ..]) => program,
+        _ => &entry.application_path,
+    }
+}
+
+/// Formats arguments as they might be typed, quoting any that contain spaces
+fn format_args(args: &[String]) -> String {
+    if args.is_empty() {
+        return String::from("-");
+    }
+
+    args.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("{:?}", arg)
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn format_flag(value: bool) -> String {
+    String::from(if value { "yes" } else { "no" })
+}
+
+fn get_state(entry: &WindowDataEntry) -> &'static str {
+    if entry.minimized {
+        "minimized"
+    } else if entry.maximised {
+        "maximized"
+    } else {
+        "normal"
+    }
+}
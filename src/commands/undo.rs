@@ -0,0 +1,186 @@
+use super::load::{arrange_windows, print_load_report};
+use crate::{
+    config::OtherWindows,
+    data::{read_latest_undo_snapshot, ArrangementSettings, ReportFormat, UndoSnapshot},
+    platform::WindowSystem,
+    printinfo,
+    utils::{LoadSettings, WaitPolicy},
+    CONFIG,
+};
+use anyhow::{bail, Context, Result};
+use std::{fs, path::Path};
+
+pub fn undo(window_system: &dyn WindowSystem) -> Result<()> {
+    let (snapshot, file_path) = match read_latest_undo_snapshot()? {
+        Some(data) => data,
+        None => bail!("There is no load to undo"),
+    };
+
+    printinfo!(
+        "restoring the arrangement from before '{}' was loaded",
+        snapshot.loaded
+    );
+    if !snapshot.closed.is_empty() {
+        printinfo!(
+            "{} window(s) closed by the load will be relaunched",
+            snapshot.closed.len()
+        );
+    }
+    if !snapshot.minimized.is_empty() {
+        printinfo!(
+            "{} window(s) minimized by the load will be restored",
+            snapshot.minimized.len()
+        );
+    }
+
+    let settings = ArrangementSettings::default();
+    restore_snapshot(
+        window_system,
+        &snapshot,
+        &file_path,
+        &LoadSettings::from_config(
+            &CONFIG,
+            &settings,
+            WaitPolicy::from_config(&CONFIG, &settings, None, None, None, None),
+            Some(OtherWindows::Leave),
+            None,
+        ),
+    )
+}
+
+/// Moves the windows in an undo snapshot back to where they were, removing the snapshot once every window has been restored
+///
+/// A snapshot that could not be fully restored is kept, so that undo can be retried (for example, after starting an application that failed to launch)
+///
+/// # Arguments
+///
+/// * `window_system` - The window system to restore windows in
+/// * `snapshot` - The snapshot to restore
+/// * `file_path` - The path to the snapshot's file
+/// * `settings` - How the snapshot's arrangement is loaded
+///
+/// # Errors
+///
+/// Returns an error if the snapshot could not be loaded, any window could not be restored, or the snapshot could not be removed
+fn restore_snapshot(
+    window_system: &dyn WindowSystem,
+    snapshot: &UndoSnapshot,
+    file_path: &Path,
+    settings: &LoadSettings,
+) -> Result<()> {
+    let report = arrange_windows(window_system, &snapshot.window_data, settings)?;
+
+    print_load_report(&report, ReportFormat::Table)?;
+
+    let failed_count = report.failed_count();
+    if failed_count > 0 {
+        bail!(
+            "{} of {} windows could not be restored - the snapshot has been kept, so undo can be tried again",
+            failed_count,
+            report.entries.len()
+        );
+    }
+
+    // The snapshot has been used, so the next undo goes back to the load before this one
+    fs::remove_file(file_path).with_context(|| "Failed to remove undo snapshot")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::ConfigData,
+        data::{WindowPosition, WindowSize},
+        platform::{FakeWindow, FakeWindowSystem},
+    };
+    use std::{env, path::PathBuf, process};
+
+    const EDITOR: &str = "/usr/bin/editor";
+
+    /// Gets a snapshot with one entry for the editor, placed at 100, 50
+    fn snapshot() -> UndoSnapshot {
+        serde_json::from_str(
+            r#"{
+                "taken_at": 0,
+                "loaded": "work",
+                "window_data": {
+                    "version": 2,
+                    "name": "work",
+                    "data": [{
+                        "application_path": "/usr/bin/editor",
+                        "application_args": [],
+                        "launch": true,
+                        "reposition": true,
+                        "position": {"top": 50, "left": 100},
+                        "size": {"width": 800, "height": 600},
+                        "minimized": false,
+                        "maximised": false
+                    }]
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    /// Writes a stand-in for a snapshot's file, with a name unique to the test
+    fn snapshot_file(test_name: &str) -> PathBuf {
+        let file_path = env::temp_dir().join(format!(
+            "windough-undo-{}-{}.json",
+            process::id(),
+            test_name
+        ));
+        fs::write(&file_path, "{}").unwrap();
+        file_path
+    }
+
+    fn settings() -> LoadSettings {
+        let config: ConfigData = serde_json::from_str(r#"{"wait_timeout": 5000}"#).unwrap();
+        let settings = ArrangementSettings::default();
+        LoadSettings::from_config(
+            &config,
+            &settings,
+            WaitPolicy::from_config(&config, &settings, None, None, None, None),
+            Some(OtherWindows::Leave),
+            None,
+        )
+    }
+
+    #[test]
+    fn removes_snapshot_once_restored() {
+        let window_system = FakeWindowSystem::new();
+        let id = window_system.open_window(FakeWindow::new(
+            EDITOR,
+            "Window",
+            WindowPosition { top: 0, left: 0 },
+            WindowSize {
+                width: 400,
+                height: 300,
+            },
+        ));
+        let file_path = snapshot_file("restored");
+
+        restore_snapshot(&window_system, &snapshot(), &file_path, &settings()).unwrap();
+
+        assert_eq!(
+            window_system.window(id).unwrap().position,
+            WindowPosition { top: 50, left: 100 }
+        );
+        assert!(!file_path.exists());
+    }
+
+    #[test]
+    fn keeps_snapshot_when_window_is_not_restored() {
+        // Nothing is scripted for the editor, so relaunching it fails
+        let window_system = FakeWindowSystem::new();
+        let file_path = snapshot_file("not-restored");
+
+        let error =
+            restore_snapshot(&window_system, &snapshot(), &file_path, &settings()).unwrap_err();
+
+        assert!(format!("{:#}", error).contains("undo can be tried again"));
+        assert!(file_path.exists());
+        fs::remove_file(file_path).unwrap();
+    }
+}
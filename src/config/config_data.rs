@@ -1,3 +1,5 @@
+use super::{WindowFilter, WindowFilters, WindowRule};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -6,6 +8,85 @@ pub struct ConfigData {
     pub retry_count: usize,
     #[serde(default = "retry_interval_default")]
     pub retry_interval: usize,
+    #[serde(default = "capture_args_default")]
+    pub capture_args: bool,
+    #[serde(default = "undo_history_size_default")]
+    pub undo_history_size: usize,
+    /// How many previous versions of each arrangement are kept as backups when it is written
+    #[serde(default = "backup_count_default")]
+    pub backup_count: usize,
+    /// How many revisions of each arrangement are kept in its history (0 = no history)
+    #[serde(default = "history_size_default")]
+    pub history_size: usize,
+    /// How old (in days) revisions can get before they are removed from the history - if not set, revisions are only removed by `history_size`
+    #[serde(default)]
+    pub history_max_age: Option<usize>,
+    /// How long (in milliseconds) each window is waited for - if not set, this is `retry_count` * `retry_interval`
+    #[serde(default)]
+    pub wait_timeout: Option<usize>,
+    #[serde(default)]
+    pub backoff: Backoff,
+    #[serde(default = "max_retry_interval_default")]
+    pub max_retry_interval: usize,
+    #[serde(default = "verify_placement_default")]
+    pub verify_placement: bool,
+    /// How far (in pixels) a window may be from where it was placed before it is moved back
+    #[serde(default = "verify_tolerance_default")]
+    pub verify_tolerance: usize,
+    /// How many times a window that has drifted is moved back into place
+    #[serde(default = "verify_retries_default")]
+    pub verify_retries: usize,
+    /// How long (in milliseconds) windows are given to settle before their placement is checked
+    #[serde(default = "verify_delay_default")]
+    pub verify_delay: usize,
+    /// If not empty, `save` only saves windows that match one of these filters
+    #[serde(default)]
+    pub include_windows: Vec<WindowFilter>,
+    /// Windows that match any of these filters are never saved, matched or moved
+    #[serde(default = "exclude_windows_default")]
+    pub exclude_windows: Vec<WindowFilter>,
+    /// Settings applied to matching windows in every arrangement, when saving and again when loading
+    #[serde(default)]
+    pub rules: Vec<WindowRule>,
+    #[serde(default)]
+    pub other_windows: OtherWindows,
+    /// How many launched applications `load` waits for at once (0 = no limit)
+    #[serde(default)]
+    pub launch_concurrency: usize,
+}
+
+impl ConfigData {
+    /// Gets the window filters set in the configuration
+    pub fn get_window_filters(&self) -> WindowFilters {
+        WindowFilters {
+            include: self.include_windows.clone(),
+            exclude: self.exclude_windows.clone(),
+        }
+    }
+}
+
+/// How the time between looks at the open windows changes while windows are waited for
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Backoff {
+    /// Always wait `retry_interval`
+    #[default]
+    Fixed,
+    /// Start at `retry_interval` and double each time, up to `max_retry_interval`
+    Exponential,
+    /// Like `Exponential`, but each wait is randomly shortened by up to half so that many waits do not line up
+    Jittered,
+}
+
+/// What `load` does with open windows that are not in the arrangement
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OtherWindows {
+    /// Leave them as they are
+    #[default]
+    Leave,
+    Close,
+    Minimize,
 }
 
 const fn retry_count_default() -> usize {
@@ -14,3 +95,37 @@ const fn retry_count_default() -> usize {
 const fn retry_interval_default() -> usize {
     750
 }
+const fn capture_args_default() -> bool {
+    true
+}
+const fn undo_history_size_default() -> usize {
+    10
+}
+const fn backup_count_default() -> usize {
+    3
+}
+const fn history_size_default() -> usize {
+    20
+}
+const fn max_retry_interval_default() -> usize {
+    5000
+}
+const fn verify_placement_default() -> bool {
+    true
+}
+const fn verify_tolerance_default() -> usize {
+    2
+}
+const fn verify_retries_default() -> usize {
+    2
+}
+const fn verify_delay_default() -> usize {
+    250
+}
+fn exclude_windows_default() -> Vec<WindowFilter> {
+    // Windows that belong to the operating system itself
+    vec![WindowFilter {
+        exe: Some(String::from("C:\\Windows\\**")),
+        ..Default::default()
+    }]
+}
@@ -0,0 +1,366 @@
+use crate::utils::write_file_atomically;
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::Chars,
+};
+
+/// The formats a configuration file can be written in, going by its extension
+#[derive(Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Gets the format of a configuration file from its extension
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extension is not `json` or `toml`
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Ok(ConfigFormat::Json),
+            Some("toml") => Ok(ConfigFormat::Toml),
+            _ => bail!(
+                "Configuration file '{}' must have a .json or .toml extension",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// A configuration file, and the values set in it
+pub struct ConfigFile {
+    pub path: PathBuf,
+    pub format: ConfigFormat,
+    /// The text of the file, used to find the line a value is set on
+    contents: String,
+    pub values: Map<String, Value>,
+}
+
+impl ConfigFile {
+    /// Finds a configuration file in a directory, preferring `<stem>.toml` to `<stem>.json`
+    ///
+    /// # Returns
+    ///
+    /// The path of the file, or `None` if neither exists
+    pub fn find(dir_path: &Path, stem: &str) -> Option<PathBuf> {
+        ["toml", "json"]
+            .iter()
+            .map(|extension| dir_path.join(format!("{}.{}", stem, extension)))
+            .find(|path| path.is_file())
+    }
+
+    /// Reads and parses a configuration file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be read, or is not a valid JSON object or TOML table
+    pub fn read(path: &Path) -> Result<Self> {
+        let format = ConfigFormat::from_path(path)?;
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Error reading config from '{}'", path.display()))?;
+
+        let value = match format {
+            ConfigFormat::Json => serde_json::from_str::<Value>(&contents)
+                .with_context(|| format!("Error parsing '{}' as JSON", path.display()))?,
+            ConfigFormat::Toml => {
+                let table: toml::Table = toml::from_str(&contents)
+                    .with_context(|| format!("Error parsing '{}' as TOML", path.display()))?;
+                serde_json::to_value(table)
+                    .with_context(|| format!("Error converting '{}'", path.display()))?
+            }
+        };
+        let values = match value {
+            Value::Object(values) => values,
+            _ => bail!(
+                "Configuration file '{}' must contain an object",
+                path.display()
+            ),
+        };
+
+        Ok(ConfigFile {
+            path: path.to_path_buf(),
+            format,
+            contents,
+            values,
+        })
+    }
+
+    /// Writes the values back to the file, in its format
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the values could not be serialized (e.g. a `null` in TOML) or the file could not be written
+    pub fn write(&self) -> Result<()> {
+        let contents = match self.format {
+            ConfigFormat::Json => serde_json::to_string_pretty(&self.values)
+                .with_context(|| "Failed to serialize config as JSON")?,
+            ConfigFormat::Toml => toml::to_string_pretty(&self.values)
+                .with_context(|| "Failed to serialize config as TOML")?,
+        };
+
+        write_file_atomically(&self.path, &contents)
+            .with_context(|| format!("Failed to write config to '{}'", self.path.display()))
+    }
+
+    /// Finds the line (starting at 1) that a key is set on, if it can be found
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key, or a dotted path to a nested key (e.g. `outer.inner`) - keys inside arrays are not found
+    pub fn find_key_line(&self, key: &str) -> Option<usize> {
+        let path: Vec<&str> = key.split('.').collect();
+        match self.format {
+            ConfigFormat::Json => find_json_key_line(&self.contents, &path),
+            ConfigFormat::Toml => find_toml_key_line(&self.contents, &path),
+        }
+    }
+}
+
+/// An object or array that a JSON scan is inside, along with the key last read in it if it is an object
+enum JsonScope {
+    Object(Option<String>),
+    Array,
+}
+
+/// Finds the line a key is set on in JSON, following the nesting of objects so that a key of the same name at another depth is not found
+fn find_json_key_line(contents: &str, path: &[&str]) -> Option<usize> {
+    let mut scopes: Vec<JsonScope> = Vec::new();
+    let mut line = 1;
+    let mut chars = contents.chars();
+
+    while let Some(character) = chars.next() {
+        match character {
+            '\n' => line += 1,
+            '{' => scopes.push(JsonScope::Object(None)),
+            '[' => scopes.push(JsonScope::Array),
+            '}' | ']' => {
+                scopes.pop();
+            }
+            ',' => {
+                if let Some(JsonScope::Object(key)) = scopes.last_mut() {
+                    *key = None;
+                }
+            }
+            '"' => {
+                let string = read_json_string(&mut chars)?;
+                // A string is a key if it is the first thing in an object or comes after a comma, otherwise it is a value
+                let Some(JsonScope::Object(key @ None)) = scopes.last_mut() else {
+                    continue;
+                };
+                *key = Some(string);
+
+                let is_match = scopes.len() == path.len()
+                    && scopes.iter().zip(path).all(|(scope, part)| {
+                        matches!(scope, JsonScope::Object(Some(key)) if key == part)
+                    });
+                if is_match {
+                    return Some(line);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// Reads the rest of a JSON string after its opening quote, returning it with escapes decoded
+fn read_json_string(chars: &mut Chars) -> Option<String> {
+    let mut raw = String::new();
+    while let Some(character) = chars.next() {
+        match character {
+            '"' => return serde_json::from_str(&format!("\"{}\"", raw)).ok(),
+            '\\' => {
+                raw.push(character);
+                raw.push(chars.next()?);
+            }
+            _ => raw.push(character),
+        }
+    }
+
+    None
+}
+
+/// Finds the line a key is set on in TOML, following table headers and dotted keys so that a key of the same name in another table is not found
+///
+/// A table is found at its first header or dotted key, whichever comes first
+fn find_toml_key_line(contents: &str, path: &[&str]) -> Option<usize> {
+    let mut table: Vec<String> = Vec::new();
+    // Lines inside a multi-line array or string are part of a value, not keys
+    let mut open_brackets = 0;
+    let mut string_delimiter: Option<&str> = None;
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if let Some(delimiter) = string_delimiter {
+            if line.contains(delimiter) {
+                string_delimiter = None;
+            }
+            continue;
+        }
+        if open_brackets > 0 {
+            open_brackets += count_open_brackets(line);
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let full_key = if let Some(header) = line.strip_prefix('[') {
+            let header = header.trim_start_matches('[');
+            table = split_toml_key(&header[..header.find(']')?]);
+            table.clone()
+        } else if let Some((key, value)) = line.split_once('=') {
+            open_brackets = count_open_brackets(value);
+            string_delimiter = ["\"\"\"", "'''"]
+                .into_iter()
+                .find(|delimiter| value.matches(delimiter).count() % 2 == 1);
+            table.iter().cloned().chain(split_toml_key(key)).collect()
+        } else {
+            continue;
+        };
+
+        if full_key.len() >= path.len() && full_key.iter().zip(path).all(|(part, key)| part == key)
+        {
+            return Some(index + 1);
+        }
+    }
+
+    None
+}
+
+/// Splits a TOML key (e.g. `outer."inner.key"`) into its parts, without their quotes
+fn split_toml_key(key: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quote = None;
+    for character in key.chars() {
+        match (quote, character) {
+            (None, '"' | '\'') => quote = Some(character),
+            (Some(open), _) if character == open => quote = None,
+            (None, '.') => parts.push(String::new()),
+            (None, _) if character.is_whitespace() => (),
+            _ => parts.last_mut().unwrap().push(character),
+        }
+    }
+
+    parts
+}
+
+/// Counts how many more brackets a line opens than it closes, ignoring any in strings or comments
+fn count_open_brackets(line: &str) -> i32 {
+    let mut count = 0;
+    let mut quote = None;
+    for character in line.chars() {
+        match (quote, character) {
+            (None, '"' | '\'') => quote = Some(character),
+            (Some(open), _) if character == open => quote = None,
+            (None, '[') => count += 1,
+            (None, ']') => count -= 1,
+            (None, '#') => break,
+            _ => (),
+        }
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_file(format: ConfigFormat, contents: &str) -> ConfigFile {
+        ConfigFile {
+            path: PathBuf::from("config"),
+            format,
+            contents: contents.to_string(),
+            values: Map::new(),
+        }
+    }
+
+    #[test]
+    fn finds_top_level_json_key_rather_than_nested_key_of_same_name() {
+        let file = config_file(
+            ConfigFormat::Json,
+            r#"{
+  "rules": [{ "exe": "*/firefox", "launch": false }],
+  "other": {
+    "launch": true
+  },
+  "title": "\"launch\": false",
+  "launch": false
+}"#,
+        );
+
+        assert_eq!(file.find_key_line("launch"), Some(7));
+        assert_eq!(file.find_key_line("rules"), Some(2));
+        assert_eq!(file.find_key_line("exe"), None);
+    }
+
+    #[test]
+    fn finds_nested_json_key_by_dotted_path() {
+        let file = config_file(
+            ConfigFormat::Json,
+            r#"{
+  "launch": true,
+  "other": {"inner": {
+    "launch": false
+  }}
+}"#,
+        );
+
+        assert_eq!(file.find_key_line("other.inner.launch"), Some(4));
+        assert_eq!(file.find_key_line("other.launch"), None);
+    }
+
+    #[test]
+    fn finds_top_level_toml_key_rather_than_key_in_table() {
+        let file = config_file(
+            ConfigFormat::Toml,
+            r#"# launch = true
+exclude_windows = [
+  "exe=*/launch",
+  "launch = true",
+]
+description = """
+launch = true
+"""
+retry_count = 5
+
+[other]
+launch = true
+
+[[rules]]
+exe = "*/firefox"
+"#,
+        );
+
+        assert_eq!(file.find_key_line("retry_count"), Some(9));
+        assert_eq!(file.find_key_line("launch"), None);
+        assert_eq!(file.find_key_line("rules"), Some(14));
+        assert_eq!(file.find_key_line("exe"), None);
+    }
+
+    #[test]
+    fn finds_nested_toml_key_by_dotted_path() {
+        let file = config_file(
+            ConfigFormat::Toml,
+            r#"launch = false
+outer.inner = 1
+"quoted.key" = 2
+
+[other]
+launch = true
+"#,
+        );
+
+        assert_eq!(file.find_key_line("outer"), Some(2));
+        assert_eq!(file.find_key_line("outer.inner"), Some(2));
+        assert_eq!(file.find_key_line("other.launch"), Some(6));
+        assert_eq!(file.find_key_line("quoted"), None);
+    }
+}
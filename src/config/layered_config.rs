@@ -0,0 +1,298 @@
+use super::{config_file::ConfigFile, ConfigData};
+use crate::{
+    printwarning,
+    utils::{resource_exists, ResourceType},
+    PROJECT_DIRS,
+};
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::{
+    collections::HashMap,
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+/// The prefix of environment variables that set configuration values (e.g. `WINDOUGH_RETRY_COUNT`)
+const ENV_PREFIX: &str = "WINDOUGH_";
+
+/// Where a configuration value was set
+#[derive(Clone)]
+pub enum ConfigOrigin {
+    Default,
+    File { path: PathBuf, line: Option<usize> },
+    Environment(String),
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigOrigin::Default => write!(formatter, "default"),
+            ConfigOrigin::File {
+                path,
+                line: Some(line),
+            } => write!(formatter, "file:{}:{}", path.display(), line),
+            ConfigOrigin::File { path, line: None } => write!(formatter, "file:{}", path.display()),
+            ConfigOrigin::Environment(name) => write!(formatter, "env:{}", name),
+        }
+    }
+}
+
+/// The configuration values from every layer, merged in order of precedence, along with where each was set
+///
+/// The layers are (from lowest to highest precedence): built-in defaults, the system file, the user file, the project file and `WINDOUGH_*` environment variables. Command-line flags are applied on top of these by each command
+pub struct LayeredConfig {
+    pub values: Map<String, Value>,
+    pub origins: HashMap<String, ConfigOrigin>,
+}
+
+impl LayeredConfig {
+    /// Reads every layer of the configuration, warning about keys that are not configuration values
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if one of the configuration files could not be read or parsed
+    pub fn load() -> Result<Self> {
+        let mut file_paths = Vec::new();
+        file_paths.extend(ConfigFile::find(&get_system_config_dir(), "config"));
+        file_paths.push(get_user_config_path()?);
+        file_paths.extend(find_project_config_path());
+
+        let env_vars = env::vars()
+            .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+            .collect();
+
+        LayeredConfig::from_layers(&file_paths, env_vars)
+    }
+
+    /// Merges the defaults, then each configuration file in order, then the environment variables
+    ///
+    /// # Arguments
+    ///
+    /// * `file_paths` - The configuration files, from lowest to highest precedence
+    /// * `env_vars` - The `WINDOUGH_*` environment variables, as names and values
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if one of the configuration files could not be read or parsed
+    fn from_layers(file_paths: &[PathBuf], mut env_vars: Vec<(String, String)>) -> Result<Self> {
+        let values = get_default_values();
+        let origins = values
+            .keys()
+            .map(|key| (key.clone(), ConfigOrigin::Default))
+            .collect();
+        let mut config = LayeredConfig { values, origins };
+
+        for file_path in file_paths {
+            let file = ConfigFile::read(file_path)?;
+            for (key, value) in &file.values {
+                let origin = ConfigOrigin::File {
+                    path: file.path.clone(),
+                    line: file.find_key_line(key),
+                };
+                config.set_value(key, value.clone(), origin);
+            }
+        }
+
+        env_vars.sort();
+        for (name, value) in env_vars {
+            let key = name[ENV_PREFIX.len()..].to_lowercase();
+            // Values are read as JSON where possible (numbers, booleans, arrays), and as plain strings otherwise
+            let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+            config.set_value(&key, value, ConfigOrigin::Environment(name));
+        }
+
+        Ok(config)
+    }
+
+    /// Converts the merged values into configuration data
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the value and where it was set if a value is not valid
+    pub fn to_config_data(&self) -> Result<ConfigData> {
+        for (key, value) in &self.values {
+            validate_value(key, value).with_context(|| {
+                format!(
+                    "Invalid configuration value '{}' (set in {})",
+                    key,
+                    self.get_origin(key)
+                )
+            })?;
+        }
+
+        serde_json::from_value(Value::Object(self.values.clone()))
+            .with_context(|| "Error parsing configuration")
+    }
+
+    /// Gets where a value was set
+    pub fn get_origin(&self, key: &str) -> ConfigOrigin {
+        self.origins
+            .get(key)
+            .cloned()
+            .unwrap_or(ConfigOrigin::Default)
+    }
+
+    /// Overrides a value, or warns if the key is not a configuration value
+    fn set_value(&mut self, key: &str, value: Value, origin: ConfigOrigin) {
+        if !self.values.contains_key(key) {
+            printwarning!("unknown configuration key '{}' in {}", key, origin);
+            return;
+        }
+
+        self.values.insert(key.to_string(), value);
+        self.origins.insert(key.to_string(), origin);
+    }
+}
+
+/// Gets the built-in default of every configuration value
+pub fn get_default_values() -> Map<String, Value> {
+    match serde_json::from_str::<ConfigData>("{}").and_then(serde_json::to_value) {
+        Ok(Value::Object(values)) => values,
+        _ => Map::new(),
+    }
+}
+
+/// Checks that a value is valid for a configuration key
+///
+/// # Errors
+///
+/// Returns an error if the value has the wrong type or form for the key
+pub fn validate_value(key: &str, value: &Value) -> Result<()> {
+    let mut values = Map::new();
+    values.insert(key.to_string(), value.clone());
+    serde_json::from_value::<ConfigData>(Value::Object(values))?;
+    Ok(())
+}
+
+/// Gets the path of the user's configuration file (`config.toml` or `config.json` in the config directory), creating an empty `config.json` if neither exists
+///
+/// # Errors
+///
+/// Returns an error if the config directory or file could not be created
+pub fn get_user_config_path() -> Result<PathBuf> {
+    let dir_path = PROJECT_DIRS.config_dir();
+    resource_exists(dir_path, ResourceType::Dir, true)?;
+    if let Some(file_path) = ConfigFile::find(dir_path, "config") {
+        return Ok(file_path);
+    }
+
+    let file_path = dir_path.join("config.json");
+    fs::write(&file_path, "{}").with_context(|| "Failed to create config file")?;
+    Ok(file_path)
+}
+
+/// Gets the directory of the system-wide configuration file, which applies to every user
+fn get_system_config_dir() -> PathBuf {
+    if cfg!(windows) {
+        let program_data = env::var_os("ProgramData").unwrap_or_else(|| "C:\\ProgramData".into());
+        Path::new(&program_data).join("Windough")
+    } else {
+        PathBuf::from("/etc/windough")
+    }
+}
+
+/// Finds the project configuration file (`.windough.toml` or `.windough.json`) in the current directory or the nearest parent directory that has one
+fn find_project_config_path() -> Option<PathBuf> {
+    let current_dir = env::current_dir().ok()?;
+    current_dir
+        .ancestors()
+        .find_map(|dir_path| ConfigFile::find(dir_path, ".windough"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes the files for a test to a directory of its own, returning their paths in the order given
+    fn write_files(test_name: &str, files: &[(&str, &str)]) -> Vec<PathBuf> {
+        let dir_path = env::temp_dir().join(format!(
+            "windough-config-{}-{}",
+            std::process::id(),
+            test_name
+        ));
+        fs::create_dir_all(&dir_path).unwrap();
+        files
+            .iter()
+            .map(|(file_name, contents)| {
+                let file_path = dir_path.join(file_name);
+                fs::write(&file_path, contents).unwrap();
+                file_path
+            })
+            .collect()
+    }
+
+    #[test]
+    fn later_layers_override_earlier_ones() {
+        let file_paths = write_files(
+            "layers",
+            &[
+                (
+                    "system.json",
+                    r#"{"retry_count": 1, "backup_count": 1, "history_size": 1}"#,
+                ),
+                ("user.toml", "retry_count = 2\nbackup_count = 2\n"),
+                ("project.json", "{\n  \"retry_count\": 3\n}"),
+            ],
+        );
+        let env_vars = vec![(String::from("WINDOUGH_RETRY_COUNT"), String::from("4"))];
+
+        let config = LayeredConfig::from_layers(&file_paths, env_vars).unwrap();
+
+        assert_eq!(config.values["retry_count"], 4);
+        assert_eq!(
+            config.get_origin("retry_count").to_string(),
+            "env:WINDOUGH_RETRY_COUNT"
+        );
+        assert_eq!(config.values["backup_count"], 2);
+        assert_eq!(
+            config.get_origin("backup_count").to_string(),
+            format!("file:{}:2", file_paths[1].display())
+        );
+        assert_eq!(config.values["history_size"], 1);
+        assert_eq!(
+            config.get_origin("history_size").to_string(),
+            format!("file:{}:1", file_paths[0].display())
+        );
+        assert_eq!(config.get_origin("verify_delay").to_string(), "default");
+
+        fs::remove_dir_all(file_paths[0].parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn project_file_overrides_user_file_without_environment() {
+        let file_paths = write_files(
+            "project",
+            &[
+                ("user.json", r#"{"retry_count": 2}"#),
+                ("project.toml", "# Project settings\nretry_count = 3\n"),
+            ],
+        );
+
+        let config = LayeredConfig::from_layers(&file_paths, Vec::new()).unwrap();
+
+        assert_eq!(config.values["retry_count"], 3);
+        assert_eq!(
+            config.get_origin("retry_count").to_string(),
+            format!("file:{}:2", file_paths[1].display())
+        );
+
+        fs::remove_dir_all(file_paths[0].parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn environment_values_are_read_as_json_where_possible() {
+        let env_vars = vec![
+            (String::from("WINDOUGH_CAPTURE_ARGS"), String::from("false")),
+            (
+                String::from("WINDOUGH_BACKOFF"),
+                String::from("exponential"),
+            ),
+        ];
+
+        let config = LayeredConfig::from_layers(&[], env_vars).unwrap();
+
+        assert_eq!(config.values["capture_args"], false);
+        assert_eq!(config.values["backoff"], "exponential");
+        assert!(config.to_config_data().is_ok());
+    }
+}
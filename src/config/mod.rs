@@ -1,5 +1,15 @@
 mod config_data;
+mod config_file;
 mod get_config;
+mod layered_config;
+mod window_filter;
+mod window_rule;
 
-pub use config_data::ConfigData;
+pub use config_data::{Backoff, ConfigData, OtherWindows};
+pub use config_file::ConfigFile;
 pub use get_config::get_config;
+pub use layered_config::{
+    get_default_values, get_user_config_path, validate_value, ConfigOrigin, LayeredConfig,
+};
+pub use window_filter::{WindowFacts, WindowFilter, WindowFilters};
+pub use window_rule::WindowRule;
@@ -0,0 +1,283 @@
+use crate::data::WindowSize;
+use anyhow::{bail, Context, Result};
+use globset::GlobBuilder;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+const FILTER_KEYS: [&str; 6] = ["exe", "title", "class", "monitor", "min-size", "minimized"];
+
+/// A rule that picks out windows by what they are and where they are; every field that is set must match
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WindowFilter {
+    /// A glob matched against the full path of the window's executable, ignoring case (`\` and `/` are treated the same)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exe: Option<String>,
+    /// A regular expression matched against the window's title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The window's class (or app ID, under sway), ignoring case
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    /// The ID of the monitor the window is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<String>,
+    /// The smallest size a window can have to match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<WindowSize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimized: Option<bool>,
+}
+
+/// What is known about a window when checking it against filters
+pub struct WindowFacts<'a> {
+    pub module_path: &'a str,
+    pub title: &'a str,
+    pub class: Option<&'a str>,
+    pub monitor: Option<&'a str>,
+    pub size: WindowSize,
+    pub minimized: bool,
+}
+
+impl WindowFilter {
+    /// Checks whether the filter needs a window's monitor, size or minimized state (which take longer to find out)
+    pub fn needs_geometry(&self) -> bool {
+        self.monitor.is_some() || self.min_size.is_some() || self.minimized.is_some()
+    }
+
+    /// Checks whether a window matches the filter
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `exe` glob or `title` regular expression is invalid
+    pub fn matches(&self, facts: &WindowFacts) -> Result<bool> {
+        if let Some(exe) = &self.exe {
+            let glob = GlobBuilder::new(&exe.replace('\\', "/"))
+                .case_insensitive(true)
+                .build()
+                .with_context(|| format!("Invalid executable glob '{}'", exe))?
+                .compile_matcher();
+            if !glob.is_match(facts.module_path.replace('\\', "/")) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(title) = &self.title {
+            let pattern =
+                Regex::new(title).with_context(|| format!("Invalid title pattern '{}'", title))?;
+            if !pattern.is_match(facts.title) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(class) = &self.class {
+            match facts.class {
+                Some(window_class) if window_class.eq_ignore_ascii_case(class) => (),
+                _ => return Ok(false),
+            }
+        }
+
+        if self.monitor.is_some() && self.monitor.as_deref() != facts.monitor {
+            return Ok(false);
+        }
+
+        if let Some(min_size) = &self.min_size {
+            if facts.size.width < min_size.width || facts.size.height < min_size.height {
+                return Ok(false);
+            }
+        }
+
+        if self.minimized.is_some() && self.minimized != Some(facts.minimized) {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+}
+
+/// Parses a filter from the command line, as comma-separated `key=value` pairs (e.g. `exe=*\chrome.exe,minimized=false`)
+///
+/// The keys are `exe`, `title`, `class`, `monitor`, `min-size` (as `WIDTHxHEIGHT`) and `minimized`, and each needs a value. A comma that is not followed by one of the keys is kept as part of the value, so titles can contain commas
+impl FromStr for WindowFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let mut pairs: Vec<String> = Vec::new();
+        for part in value.split(',') {
+            let starts_pair = FILTER_KEYS
+                .iter()
+                .any(|key| part.trim_start().starts_with(&format!("{}=", key)));
+            match pairs.last_mut() {
+                Some(previous) if !starts_pair => {
+                    previous.push(',');
+                    previous.push_str(part);
+                }
+                _ => pairs.push(part.to_string()),
+            }
+        }
+
+        let mut filter = WindowFilter::default();
+        for pair in pairs {
+            let (key, value) = match pair.split_once('=') {
+                Some(data) => data,
+                None => bail!(
+                    "Expected 'key=value' in filter, but found '{}' (keys are: {})",
+                    pair,
+                    FILTER_KEYS.join(", ")
+                ),
+            };
+
+            let key = key.trim();
+            if FILTER_KEYS.contains(&key) && value.trim().is_empty() {
+                bail!("Expected a value for '{}' in filter, but found none", key);
+            }
+
+            match key {
+                "exe" => filter.exe = Some(value.to_string()),
+                "title" => filter.title = Some(value.to_string()),
+                "class" => filter.class = Some(value.to_string()),
+                "monitor" => filter.monitor = Some(value.to_string()),
+                "min-size" => {
+                    let (width, height) = value
+                        .split_once('x')
+                        .and_then(|(width, height)| {
+                            Some((width.trim().parse().ok()?, height.trim().parse().ok()?))
+                        })
+                        .with_context(|| {
+                            format!("Expected 'min-size' as WIDTHxHEIGHT, but found '{}'", value)
+                        })?;
+                    filter.min_size = Some(WindowSize { width, height });
+                }
+                "minimized" => {
+                    filter.minimized = Some(value.trim().parse().with_context(|| {
+                        format!(
+                            "Expected 'minimized' as true or false, but found '{}'",
+                            value
+                        )
+                    })?)
+                }
+                key => bail!(
+                    "Unknown filter key '{}' (keys are: {})",
+                    key,
+                    FILTER_KEYS.join(", ")
+                ),
+            }
+        }
+
+        Ok(filter)
+    }
+}
+
+/// The filters that decide which windows Windough handles: a window is handled if it matches any include filter (or there are none), and no exclude filter
+#[derive(Clone, Default)]
+pub struct WindowFilters {
+    pub include: Vec<WindowFilter>,
+    pub exclude: Vec<WindowFilter>,
+}
+
+impl WindowFilters {
+    /// Checks whether any of the filters need a window's monitor, size or minimized state
+    pub fn needs_geometry(&self) -> bool {
+        self.include
+            .iter()
+            .chain(&self.exclude)
+            .any(|filter| filter.needs_geometry())
+    }
+
+    /// Checks whether a window passes the filters
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if one of the filters is invalid
+    pub fn is_included(&self, facts: &WindowFacts) -> Result<bool> {
+        let mut included = self.include.is_empty();
+        for filter in &self.include {
+            if filter.matches(facts)? {
+                included = true;
+                break;
+            }
+        }
+        if !included {
+            return Ok(false);
+        }
+
+        for filter in &self.exclude {
+            if filter.matches(facts)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(value: &str) -> String {
+        match value.parse::<WindowFilter>() {
+            Ok(_) => panic!("'{}' was parsed as a filter", value),
+            Err(error) => format!("{:#}", error),
+        }
+    }
+
+    #[test]
+    fn parses_every_key() {
+        let filter: WindowFilter =
+            "exe=*/firefox,title=Inbox,class=Navigator,monitor=DP-1,min-size=800x600,minimized=false"
+                .parse()
+                .unwrap();
+
+        assert_eq!(filter.exe.as_deref(), Some("*/firefox"));
+        assert_eq!(filter.title.as_deref(), Some("Inbox"));
+        assert_eq!(filter.class.as_deref(), Some("Navigator"));
+        assert_eq!(filter.monitor.as_deref(), Some("DP-1"));
+        assert_eq!(
+            filter.min_size,
+            Some(WindowSize {
+                width: 800,
+                height: 600
+            })
+        );
+        assert_eq!(filter.minimized, Some(false));
+    }
+
+    #[test]
+    fn keeps_commas_that_do_not_start_a_pair() {
+        let filter: WindowFilter = r"title=Inbox, Drafts,exe=*\thunderbird.exe"
+            .parse()
+            .unwrap();
+
+        assert_eq!(filter.title.as_deref(), Some("Inbox, Drafts"));
+        assert_eq!(filter.exe.as_deref(), Some(r"*\thunderbird.exe"));
+    }
+
+    #[test]
+    fn rejects_unknown_key() {
+        assert!(parse_error("name=Inbox,exe=*/firefox").contains("Unknown filter key 'name'"));
+    }
+
+    #[test]
+    fn rejects_pair_without_equals_sign() {
+        assert!(parse_error("firefox").contains("Expected 'key=value' in filter"));
+    }
+
+    #[test]
+    fn rejects_malformed_min_size() {
+        for value in ["min-size=800", "min-size=800x", "min-size=wide x tall"] {
+            assert!(parse_error(value).contains("Expected 'min-size' as WIDTHxHEIGHT"));
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_minimized() {
+        assert!(parse_error("minimized=yes").contains("Expected 'minimized' as true or false"));
+    }
+
+    #[test]
+    fn rejects_empty_value() {
+        assert!(parse_error("exe=").contains("Expected a value for 'exe'"));
+        assert!(parse_error("exe=*/firefox,title= ").contains("Expected a value for 'title'"));
+    }
+}
@@ -0,0 +1,29 @@
+use super::WindowFilter;
+use crate::data::{WindowPosition, WindowSize};
+use serde::{Deserialize, Serialize};
+
+/// Settings applied to every arrangement entry whose window matches a filter, whichever arrangement it is in
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct WindowRule {
+    /// Which windows the rule applies to
+    #[serde(flatten)]
+    pub filter: WindowFilter,
+    /// Overrides whether the application is launched by `load`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch: Option<bool>,
+    /// Overrides whether the window is moved and resized by `load`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reposition: Option<bool>,
+    /// The arguments the application is launched with, if the entry has none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_args: Option<Vec<String>>,
+    /// The program and arguments to launch instead of the application itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch_command: Option<Vec<String>>,
+    /// Where the window is always placed, whatever position was saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<WindowPosition>,
+    /// The size the window is always given, whatever size was saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<WindowSize>,
+}
@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+/// Gets the directory arrangements, their backups and their history are kept in
+#[cfg(not(test))]
+pub fn get_data_dir() -> PathBuf {
+    crate::PROJECT_DIRS.data_dir().to_path_buf()
+}
+
+/// Gets a directory of the test thread's own, under the system's temporary directory, so that tests never touch real data or each other's
+#[cfg(test)]
+pub fn get_data_dir() -> PathBuf {
+    thread_local! {
+        static TEST_DATA_DIR: PathBuf = std::env::temp_dir().join(format!(
+            "windough-test-{}-{:016x}",
+            std::process::id(),
+            fastrand::u64(..)
+        ));
+    }
+    TEST_DATA_DIR.with(PathBuf::clone)
+}
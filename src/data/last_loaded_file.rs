@@ -0,0 +1,109 @@
+use super::get_data_dir;
+use crate::utils::{resource_exists, ResourceType};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+/// When each arrangement was last loaded, by name
+type LastLoaded = BTreeMap<String, DateTime<Utc>>;
+
+/// Gets the path to the file that records when each arrangement was last loaded (kept apart from the arrangements, so loading one does not change its file)
+fn get_last_loaded_path() -> PathBuf {
+    get_data_dir().join("state").join("last_loaded.json")
+}
+
+/// Reads when each arrangement was last loaded
+///
+/// # Errors
+///
+/// Returns an error if the file exists but could not be read or parsed
+pub fn read_last_loaded() -> Result<LastLoaded> {
+    let file_path = get_last_loaded_path();
+    if !resource_exists(&file_path, ResourceType::File, false)? {
+        return Ok(LastLoaded::new());
+    }
+
+    let json_string =
+        fs::read_to_string(file_path).with_context(|| "Error reading last loaded times")?;
+    serde_json::from_str(&json_string).with_context(|| "Error parsing last loaded times")
+}
+
+/// Records that an arrangement was loaded just now
+///
+/// # Errors
+///
+/// Returns an error if the file could not be read or written
+pub fn record_last_loaded(name: &str) -> Result<()> {
+    let mut last_loaded = read_last_loaded()?;
+    last_loaded.insert(name.to_string(), Utc::now());
+    write_last_loaded(&last_loaded)
+}
+
+/// Moves when an arrangement was last loaded to its new name, after it has been renamed
+///
+/// # Errors
+///
+/// Returns an error if the file could not be read or written
+pub fn rename_last_loaded(old_name: &str, new_name: &str) -> Result<()> {
+    let mut last_loaded = read_last_loaded()?;
+    match last_loaded.remove(old_name) {
+        Some(time) => last_loaded.insert(new_name.to_string(), time),
+        None => last_loaded.remove(new_name),
+    };
+    write_last_loaded(&last_loaded)
+}
+
+/// Forgets when an arrangement was last loaded, after it has been removed
+///
+/// # Errors
+///
+/// Returns an error if the file could not be read or written
+pub fn remove_last_loaded(name: &str) -> Result<()> {
+    let mut last_loaded = read_last_loaded()?;
+    if last_loaded.remove(name).is_some() {
+        write_last_loaded(&last_loaded)?;
+    }
+    Ok(())
+}
+
+fn write_last_loaded(last_loaded: &LastLoaded) -> Result<()> {
+    let file_path = get_last_loaded_path();
+    if let Some(dir_path) = file_path.parent() {
+        resource_exists(dir_path, ResourceType::Dir, true)?;
+    }
+    let json_string = serde_json::to_string(last_loaded)
+        .with_context(|| "Error formatting last loaded times as JSON string")?;
+    fs::write(file_path, json_string).with_context(|| "Error writing last loaded times to file")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_only_the_removed_arrangement() {
+        record_last_loaded("home").unwrap();
+        record_last_loaded("work").unwrap();
+
+        remove_last_loaded("work").unwrap();
+        remove_last_loaded("missing").unwrap();
+
+        let last_loaded = read_last_loaded().unwrap();
+        assert!(last_loaded.contains_key("home"));
+        assert!(!last_loaded.contains_key("work"));
+        fs::remove_dir_all(get_data_dir()).unwrap();
+    }
+
+    #[test]
+    fn moves_time_to_new_name() {
+        record_last_loaded("work").unwrap();
+        let loaded_at = read_last_loaded().unwrap()["work"];
+
+        rename_last_loaded("work", "office").unwrap();
+
+        let last_loaded = read_last_loaded().unwrap();
+        assert_eq!(last_loaded.get("office"), Some(&loaded_at));
+        assert!(!last_loaded.contains_key("work"));
+        fs::remove_dir_all(get_data_dir()).unwrap();
+    }
+}
@@ -0,0 +1,135 @@
+use crate::platform::WindowId;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// How the report of a `load` is printed
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum ReportFormat {
+    Table,
+    Json,
+}
+
+/// What happened to each entry of an arrangement when it was loaded
+#[derive(Serialize)]
+pub struct LoadReport {
+    pub name: String,
+    pub entries: Vec<EntryReport>,
+    /// The windows that were closed because they are not in the arrangement
+    #[serde(skip)]
+    pub closed_windows: Vec<WindowId>,
+    /// The windows that were minimized because they are not in the arrangement
+    #[serde(skip)]
+    pub minimized_windows: Vec<WindowId>,
+}
+
+impl LoadReport {
+    /// Gets the number of entries that failed
+    pub fn failed_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.status.is_failure())
+            .count()
+    }
+}
+
+#[derive(Serialize)]
+pub struct EntryReport {
+    /// The index of the entry in the arrangement
+    pub index: usize,
+    pub application_path: String,
+    pub status: EntryStatus,
+    /// How many passes over the open windows were made before the entry's window was found (or in total, if it was not found)
+    pub attempts: usize,
+    /// How far the window ended up from where it was placed, if its placement was verified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drift: Option<PlacementDrift>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// The difference between where a window was placed and where it actually is (actual minus requested), in pixels
+#[derive(Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PlacementDrift {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl PlacementDrift {
+    /// Checks whether the window's position is within `tolerance` pixels of where it was placed
+    pub fn position_within(&self, tolerance: i32) -> bool {
+        self.left.abs() <= tolerance && self.top.abs() <= tolerance
+    }
+
+    /// Checks whether the window's size is within `tolerance` pixels of what was requested
+    pub fn size_within(&self, tolerance: i32) -> bool {
+        self.width.abs() <= tolerance && self.height.abs() <= tolerance
+    }
+
+    /// Describes the drift briefly (e.g. `x+4 w-20`), listing only the parts that are not zero
+    pub fn describe(&self) -> String {
+        let parts: Vec<String> = [
+            ("x", self.left),
+            ("y", self.top),
+            ("w", self.width),
+            ("h", self.height),
+        ]
+        .iter()
+        .filter(|(_, value)| *value != 0)
+        .map(|(name, value)| format!("{}{:+}", name, value))
+        .collect();
+
+        if parts.is_empty() {
+            String::from("0")
+        } else {
+            parts.join(" ")
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EntryStatus {
+    /// The application was launched, and its window was left where it opened
+    Launched,
+    /// The application was already running, and its window was left where it was
+    AlreadyRunning,
+    /// The application was not running and the entry does not launch it, so it was not waited for
+    Skipped,
+    /// The window was moved into place
+    Placed,
+    /// No window was found for the entry
+    NotFound,
+    /// A window was found, but did not meet the entry's readiness conditions in time
+    NotReady,
+    /// The application could not be launched
+    LaunchFailed,
+    /// The window was found but could not be moved into place
+    RepositionFailed,
+}
+
+impl EntryStatus {
+    pub fn is_failure(self) -> bool {
+        matches!(
+            self,
+            EntryStatus::NotFound
+                | EntryStatus::NotReady
+                | EntryStatus::LaunchFailed
+                | EntryStatus::RepositionFailed
+        )
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EntryStatus::Launched => "launched",
+            EntryStatus::AlreadyRunning => "already-running",
+            EntryStatus::Skipped => "skipped",
+            EntryStatus::Placed => "placed",
+            EntryStatus::NotFound => "not-found",
+            EntryStatus::NotReady => "not-ready",
+            EntryStatus::LaunchFailed => "launch-failed",
+            EntryStatus::RepositionFailed => "reposition-failed",
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+/// The schema version written by this version of Windough
+pub const CURRENT_VERSION: u64 = 2;
+
+/// Upgrades arrangement data from one schema version to the next, indexed by the version being upgraded from (minus 1)
+const MIGRATIONS: [fn(Value) -> Result<Value>; (CURRENT_VERSION - 1) as usize] = [migrate_v1_to_v2];
+
+/// Gets the schema version of raw arrangement data (files written before versioning was added are version 1)
+///
+/// # Errors
+///
+/// Returns an error if the `version` property is present but is not a positive integer
+pub fn get_version(value: &Value) -> Result<u64> {
+    match value.get("version") {
+        None => Ok(1),
+        Some(version) => match version.as_u64() {
+            Some(version) if version >= 1 => Ok(version),
+            _ => bail!("'version' property must be a positive integer"),
+        },
+    }
+}
+
+/// Upgrades raw arrangement data to the current schema version
+///
+/// # Arguments
+///
+/// * `value` - The raw arrangement data, as parsed from a file
+///
+/// # Errors
+///
+/// Returns an error if the data was written by a newer version of Windough, or a migration fails
+pub fn migrate(mut value: Value) -> Result<Value> {
+    let version = get_version(&value)?;
+    if version > CURRENT_VERSION {
+        bail!(
+            "Arrangement was saved by a newer version of Windough (schema version {}, but only up to {} is supported) - update Windough to use it",
+            version,
+            CURRENT_VERSION
+        );
+    }
+
+    for from_version in version..CURRENT_VERSION {
+        value = MIGRATIONS[(from_version - 1) as usize](value).with_context(|| {
+            format!(
+                "Failed to migrate arrangement from schema version {} to {}",
+                from_version,
+                from_version + 1
+            )
+        })?;
+        value["version"] = Value::from(from_version + 1);
+    }
+
+    Ok(value)
+}
+
+/// Version 2 adds the `version` property itself, and only optional entry properties, so there is nothing else to change
+fn migrate_v1_to_v2(value: Value) -> Result<Value> {
+    if !value.is_object() {
+        bail!("Arrangement data is not a JSON object");
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn upgrades_file_without_version() {
+        let value = json!({"name": "work", "data": []});
+
+        let migrated = migrate(value).unwrap();
+
+        assert_eq!(migrated["version"], json!(CURRENT_VERSION));
+        assert_eq!(migrated["name"], json!("work"));
+        assert_eq!(migrated["data"], json!([]));
+    }
+
+    #[test]
+    fn leaves_current_version_alone() {
+        let value = json!({"version": CURRENT_VERSION, "name": "work", "data": []});
+
+        assert_eq!(migrate(value.clone()).unwrap(), value);
+    }
+
+    #[test]
+    fn rejects_newer_version() {
+        let value = json!({"version": CURRENT_VERSION + 1, "name": "work", "data": []});
+
+        let error = migrate(value).unwrap_err();
+
+        assert!(format!("{:#}", error).contains("saved by a newer version of Windough"));
+    }
+
+    #[test]
+    fn rejects_invalid_version() {
+        for version in [json!(0), json!(-1), json!("2"), json!(1.5)] {
+            assert!(get_version(&json!({ "version": version })).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_data_that_is_not_an_object() {
+        let error = migrate(json!(["work"])).unwrap_err();
+
+        assert!(format!("{:#}", error).contains("Arrangement data is not a JSON object"));
+    }
+}
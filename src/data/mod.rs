@@ -1,3 +1,32 @@
+mod data_dir;
+mod last_loaded_file;
+mod load_report;
+mod migrations;
+mod revision;
+mod revision_history_file;
+mod undo_history_file;
+mod undo_snapshot;
 mod window_data;
+mod window_data_file;
 
-pub use window_data::{WindowData, WindowDataEntry, WindowPosition, WindowSize};
+pub use data_dir::get_data_dir;
+pub use last_loaded_file::{
+    read_last_loaded, record_last_loaded, remove_last_loaded, rename_last_loaded,
+};
+pub use load_report::{EntryReport, EntryStatus, LoadReport, PlacementDrift, ReportFormat};
+pub use migrations::{get_version, migrate, CURRENT_VERSION};
+pub use revision::Revision;
+pub use revision_history_file::{
+    get_latest_revision_number, move_revisions, prune_revisions, read_revision, read_revisions,
+    remove_revisions, write_revision,
+};
+pub use undo_history_file::{prune_undo_history, read_latest_undo_snapshot, write_undo_snapshot};
+pub use undo_snapshot::UndoSnapshot;
+pub use window_data::{
+    ArrangementSettings, MonitorData, MonitorPlacement, ScreenRect, WindowData, WindowDataEntry,
+    WindowPosition, WindowReadiness, WindowSize, WindowWorkspace,
+};
+pub use window_data_file::{
+    get_window_data_path, list_window_data_names, move_backups, parse_window_data,
+    read_window_data, remove_backups, write_window_data,
+};
@@ -0,0 +1,16 @@
+use super::WindowData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A version of an arrangement, as it was written at some point, so that it can be restored
+#[derive(Serialize, Deserialize)]
+pub struct Revision {
+    /// The number of the revision, starting at 1 and counting up with each write (numbers are not reused when old revisions are removed)
+    pub number: usize,
+    pub saved_at: DateTime<Utc>,
+    /// What wrote the arrangement (e.g. `save` or `migrate`)
+    pub source: String,
+    /// A short description of how the arrangement changed from the revision before it
+    pub summary: String,
+    pub window_data: WindowData,
+}
@@ -0,0 +1,260 @@
+use super::{get_data_dir, migrate, Revision};
+use crate::utils::{resource_exists, write_file_atomically, ResourceType};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Gets the path to the directory an arrangement's revisions are kept in
+fn get_revision_dir_path(name: &str) -> PathBuf {
+    get_data_dir().join("history").join(name)
+}
+
+/// Writes a revision to an arrangement's revision history
+///
+/// # Errors
+///
+/// Returns an error if the history directory could not be created or the file could not be written
+pub fn write_revision(revision: &Revision) -> Result<()> {
+    let json_string = serde_json::to_string(revision)
+        .with_context(|| "Error formatting revision as JSON string")?;
+
+    let dir_path = get_revision_dir_path(&revision.window_data.name);
+    resource_exists(&dir_path, ResourceType::Dir, true)?;
+
+    write_file_atomically(
+        &dir_path.join(format!("{}.json", revision.number)),
+        &json_string,
+    )
+    .with_context(|| "Error writing revision to file")
+}
+
+/// Reads every revision of an arrangement, upgrading each to the current schema version if needed
+///
+/// # Arguments
+///
+/// * `name` - The name of the arrangement (which should already be validated)
+///
+/// # Errors
+///
+/// Returns an error if the history directory could not be read, or a revision could not be read or parsed
+///
+/// # Returns
+///
+/// The revisions, oldest first (empty if the arrangement has no history)
+pub fn read_revisions(name: &str) -> Result<Vec<Revision>> {
+    get_revision_paths(name)?
+        .into_iter()
+        .map(|(_, path)| read_revision_file(&path))
+        .collect()
+}
+
+/// Reads one revision of an arrangement, upgrading it to the current schema version if needed
+///
+/// # Arguments
+///
+/// * `name` - The name of the arrangement (which should already be validated)
+/// * `number` - The number of the revision
+///
+/// # Errors
+///
+/// Returns an error if the revision does not exist or could not be read or parsed
+pub fn read_revision(name: &str, number: usize) -> Result<Revision> {
+    let file_path = get_revision_dir_path(name).join(format!("{}.json", number));
+    if !resource_exists(&file_path, ResourceType::File, false)? {
+        bail!("Arrangement '{}' has no revision {}", name, number);
+    }
+    read_revision_file(&file_path)
+}
+
+/// Gets the number of the most recent revision of an arrangement
+///
+/// # Errors
+///
+/// Returns an error if the history directory could not be read
+///
+/// # Returns
+///
+/// The number, or `None` if the arrangement has no history
+pub fn get_latest_revision_number(name: &str) -> Result<Option<usize>> {
+    Ok(get_revision_paths(name)?.last().map(|(number, _)| *number))
+}
+
+/// Removes the oldest revisions of an arrangement, so that at most `limit` are kept and none are older than `oldest` (the most recent revision is always kept)
+///
+/// # Errors
+///
+/// Returns an error if the history directory could not be read or a revision could not be removed
+pub fn prune_revisions(name: &str, limit: usize, oldest: Option<DateTime<Utc>>) -> Result<()> {
+    let paths = get_revision_paths(name)?;
+    let excess = paths.len().saturating_sub(limit.max(1));
+
+    for (index, (_, path)) in paths.iter().enumerate() {
+        if index + 1 == paths.len() {
+            break;
+        }
+        let expired = match oldest {
+            Some(oldest) => {
+                read_revision_file(path).is_ok_and(|revision| revision.saved_at < oldest)
+            }
+            None => false,
+        };
+        if index < excess || expired {
+            fs::remove_file(path).with_context(|| "Failed to remove old revision")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves the revision history of an arrangement to its new name, after it has been renamed
+///
+/// Any history the new name already has (as when a renamed arrangement replaces another) is removed first, as it belongs to the arrangement that was replaced
+///
+/// # Errors
+///
+/// Returns an error if either history directory could not be removed or moved
+pub fn move_revisions(old_name: &str, new_name: &str) -> Result<()> {
+    remove_revisions(new_name)?;
+
+    let old_dir_path = get_revision_dir_path(old_name);
+    if !resource_exists(&old_dir_path, ResourceType::Dir, false)? {
+        return Ok(());
+    }
+    fs::rename(old_dir_path, get_revision_dir_path(new_name))
+        .with_context(|| "Failed to move revision history")
+}
+
+/// Removes the whole revision history of an arrangement
+///
+/// # Errors
+///
+/// Returns an error if the history directory could not be removed
+pub fn remove_revisions(name: &str) -> Result<()> {
+    let dir_path = get_revision_dir_path(name);
+    if !resource_exists(&dir_path, ResourceType::Dir, false)? {
+        return Ok(());
+    }
+    fs::remove_dir_all(dir_path).with_context(|| "Failed to remove revision history")
+}
+
+fn read_revision_file(file_path: &Path) -> Result<Revision> {
+    let json_string =
+        fs::read_to_string(file_path).with_context(|| "Error reading revision from file")?;
+    let mut value: Value =
+        serde_json::from_str(&json_string).with_context(|| "Error parsing revision as JSON")?;
+    value["window_data"] = migrate(value["window_data"].take())?;
+    serde_json::from_value(value).with_context(|| "Error reading revision")
+}
+
+/// Gets the numbers and paths of all revisions of an arrangement, oldest first
+fn get_revision_paths(name: &str) -> Result<Vec<(usize, PathBuf)>> {
+    let dir_path = get_revision_dir_path(name);
+    if !resource_exists(&dir_path, ResourceType::Dir, false)? {
+        return Ok(Vec::new());
+    }
+
+    let mut revisions: Vec<(usize, PathBuf)> = Vec::new();
+    for entry in fs::read_dir(&dir_path).with_context(|| "Failed to read history directory")? {
+        let path = entry
+            .with_context(|| "failed to get item from history directory")?
+            .path();
+        if let Some(number) = get_number(&path) {
+            revisions.push((number, path));
+        }
+    }
+    revisions.sort();
+
+    Ok(revisions)
+}
+
+/// Gets the number of a revision from its file name, or `None` if the file is not a revision
+fn get_number(path: &Path) -> Option<usize> {
+    if path.extension()? != "json" {
+        return None;
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    /// Writes a revision of `work` saved the given number of days ago
+    fn write_revision_from(number: usize, days_ago: i64) {
+        let revision = Revision {
+            number,
+            saved_at: Utc::now() - Duration::days(days_ago),
+            source: String::from("save"),
+            summary: String::new(),
+            window_data: serde_json::from_value(json!({
+                "version": 2,
+                "name": "work",
+                "data": [],
+            }))
+            .unwrap(),
+        };
+        write_revision(&revision).unwrap();
+    }
+
+    fn revision_numbers() -> Vec<usize> {
+        get_revision_paths("work")
+            .unwrap()
+            .into_iter()
+            .map(|(number, _)| number)
+            .collect()
+    }
+
+    #[test]
+    fn removes_oldest_revisions_past_limit() {
+        for number in 1..=5 {
+            write_revision_from(number, 0);
+        }
+
+        prune_revisions("work", 3, None).unwrap();
+
+        assert_eq!(revision_numbers(), [3, 4, 5]);
+        fs::remove_dir_all(get_data_dir()).unwrap();
+    }
+
+    #[test]
+    fn removes_revisions_older_than_oldest() {
+        write_revision_from(1, 30);
+        write_revision_from(2, 10);
+        write_revision_from(3, 1);
+        write_revision_from(4, 0);
+
+        prune_revisions("work", 10, Some(Utc::now() - Duration::days(7))).unwrap();
+
+        assert_eq!(revision_numbers(), [3, 4]);
+        fs::remove_dir_all(get_data_dir()).unwrap();
+    }
+
+    #[test]
+    fn always_keeps_latest_revision() {
+        write_revision_from(1, 30);
+        write_revision_from(2, 20);
+
+        prune_revisions("work", 0, Some(Utc::now() - Duration::days(7))).unwrap();
+
+        assert_eq!(revision_numbers(), [2]);
+        fs::remove_dir_all(get_data_dir()).unwrap();
+    }
+
+    #[test]
+    fn orders_revisions_by_number_rather_than_name() {
+        for number in [2, 10, 9] {
+            write_revision_from(number, 0);
+        }
+
+        prune_revisions("work", 2, None).unwrap();
+
+        assert_eq!(revision_numbers(), [9, 10]);
+        fs::remove_dir_all(get_data_dir()).unwrap();
+    }
+}
@@ -0,0 +1,105 @@
+use super::{get_data_dir, migrate, UndoSnapshot};
+use crate::utils::{resource_exists, ResourceType};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Gets the path to the directory undo snapshots are kept in
+fn get_undo_dir_path() -> PathBuf {
+    get_data_dir().join("undo")
+}
+
+/// Writes an undo snapshot to the undo history, replacing any snapshot taken at the same time
+///
+/// # Errors
+///
+/// Returns an error if the undo directory could not be created or the file could not be written
+pub fn write_undo_snapshot(snapshot: &UndoSnapshot) -> Result<()> {
+    let json_string = serde_json::to_string(snapshot)
+        .with_context(|| "Error formatting undo snapshot as JSON string")?;
+
+    let dir_path = get_undo_dir_path();
+    resource_exists(&dir_path, ResourceType::Dir, true)?;
+
+    fs::write(
+        dir_path.join(format!("{}.json", snapshot.taken_at)),
+        json_string,
+    )
+    .with_context(|| "Error writing undo snapshot to file")?;
+
+    Ok(())
+}
+
+/// Reads the most recent undo snapshot, upgrading its arrangement to the current schema version if needed
+///
+/// # Errors
+///
+/// Returns an error if the undo directory could not be read, or the snapshot could not be read or parsed
+///
+/// # Returns
+///
+/// The snapshot and the path of its file, or `None` if the undo history is empty
+pub fn read_latest_undo_snapshot() -> Result<Option<(UndoSnapshot, PathBuf)>> {
+    let file_path = match get_undo_snapshot_paths()?.pop() {
+        Some(data) => data,
+        None => return Ok(None),
+    };
+
+    let json_string =
+        fs::read_to_string(&file_path).with_context(|| "Error reading undo snapshot from file")?;
+    let mut value: Value = serde_json::from_str(&json_string)
+        .with_context(|| "Error parsing undo snapshot as JSON")?;
+    value["window_data"] = migrate(value["window_data"].take())?;
+    let snapshot: UndoSnapshot =
+        serde_json::from_value(value).with_context(|| "Error reading undo snapshot")?;
+
+    Ok(Some((snapshot, file_path)))
+}
+
+/// Removes the oldest undo snapshots, so that at most `limit` are kept
+///
+/// # Errors
+///
+/// Returns an error if the undo directory could not be read or a snapshot could not be removed
+pub fn prune_undo_history(limit: usize) -> Result<()> {
+    let paths = get_undo_snapshot_paths()?;
+    let excess = paths.len().saturating_sub(limit);
+
+    for path in &paths[..excess] {
+        fs::remove_file(path).with_context(|| "Failed to remove old undo snapshot")?;
+    }
+
+    Ok(())
+}
+
+/// Gets the paths of all undo snapshots, oldest first
+fn get_undo_snapshot_paths() -> Result<Vec<PathBuf>> {
+    let dir_path = get_undo_dir_path();
+    if !resource_exists(&dir_path, ResourceType::Dir, false)? {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots: Vec<(u64, PathBuf)> = Vec::new();
+    for entry in fs::read_dir(&dir_path).with_context(|| "Failed to read undo directory")? {
+        let path = entry
+            .with_context(|| "failed to get item from undo directory")?
+            .path();
+        if let Some(taken_at) = get_taken_at(&path) {
+            snapshots.push((taken_at, path));
+        }
+    }
+    snapshots.sort();
+
+    Ok(snapshots.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Gets the time a snapshot was taken from its file name, or `None` if the file is not a snapshot
+fn get_taken_at(path: &Path) -> Option<u64> {
+    if path.extension()? != "json" {
+        return None;
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
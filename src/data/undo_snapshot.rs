@@ -0,0 +1,18 @@
+use super::WindowData;
+use serde::{Deserialize, Serialize};
+
+/// The arrangement of open windows just before an arrangement was loaded, so that the load can be undone
+#[derive(Serialize, Deserialize)]
+pub struct UndoSnapshot {
+    /// When the snapshot was taken, in milliseconds since the Unix epoch
+    pub taken_at: u64,
+    /// The name of the arrangement that was loaded
+    pub loaded: String,
+    pub window_data: WindowData,
+    /// The indices of the entries whose windows were closed by the load
+    #[serde(default)]
+    pub closed: Vec<usize>,
+    /// The indices of the entries whose windows were minimized by the load
+    #[serde(default)]
+    pub minimized: Vec<usize>,
+}
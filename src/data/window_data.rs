@@ -1,31 +1,154 @@
+use crate::config::{Backoff, OtherWindows};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
 pub struct WindowData {
+    /// The schema version of the data (see `data::migrations`)
+    pub version: u64,
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// When the arrangement was first saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// When the arrangement was last saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// The name of the computer the arrangement was first saved on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_on_host: Option<String>,
+    /// The version of Windough that last saved the arrangement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub windough_version: Option<String>,
     pub data: Vec<WindowDataEntry>,
+    /// The monitors that were connected when the arrangement was saved
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub monitors: Vec<MonitorData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<ArrangementSettings>,
+}
+
+/// Settings for loading a particular arrangement, which override the configuration (but not command-line flags)
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ArrangementSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_count: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_interval: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_timeout: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backoff: Option<Backoff>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub other_windows: Option<OtherWindows>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch_concurrency: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify_placement: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct WindowDataEntry {
     pub application_path: String,
     pub application_args: Vec<String>,
+    /// The program and arguments to launch instead of `application_path` and `application_args`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch_command: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_directory: Option<String>,
     pub launch: bool,
     pub reposition: bool,
     pub position: WindowPosition,
     pub size: WindowSize,
     pub minimized: bool,
     pub maximised: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_title: Option<String>,
+    /// A regular expression that a window's title must match for it to be placed by this entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_class: Option<String>,
+    /// The position of this window among the windows of the same application when it was saved, starting at 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<WindowWorkspace>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<MonitorPlacement>,
+    /// How long (in milliseconds) `load` waits for this entry's window, instead of the configured wait
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ready_when: Option<WindowReadiness>,
 }
 
-#[derive(Serialize, Deserialize)]
+/// Conditions a window must meet before `load` places it, for applications that show a splash screen or resize themselves while starting
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct WindowReadiness {
+    /// A regular expression that the window's title must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<WindowSize>,
+    /// How long (in milliseconds) the window's position and size must stay the same
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stable_for: Option<usize>,
+    /// How long ago (in seconds) the window's process must have been started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_age: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
 pub struct WindowPosition {
     pub top: i32,
     pub left: i32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
 pub struct WindowSize {
     pub width: i32,
     pub height: i32,
 }
+
+/// Where a tiling window manager keeps a window
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WindowWorkspace {
+    pub name: String,
+    pub floating: bool,
+}
+
+/// A rectangle on the virtual screen, in pixels
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ScreenRect {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// A monitor, as connected when an arrangement was saved (or as currently connected)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MonitorData {
+    /// An identifier for the monitor that stays the same across restarts (such as a device or output name)
+    pub id: String,
+    pub bounds: ScreenRect,
+    /// The part of the monitor that windows are placed in (excluding taskbars, docks and panels)
+    pub work_area: ScreenRect,
+    /// The display scale factor (1.0 = 100%)
+    pub scale: f64,
+    pub primary: bool,
+}
+
+/// Where a window is relative to the monitor it was on, so that it can be remapped if monitors change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MonitorPlacement {
+    /// The `id` of the monitor in the arrangement's `monitors`
+    pub id: String,
+    /// The window's position relative to the top-left corner of the monitor's work area
+    pub position: WindowPosition,
+    pub size: WindowSize,
+}
@@ -0,0 +1,333 @@
+use super::{get_data_dir, migrate, WindowData};
+use crate::{
+    printwarning,
+    utils::{resource_exists, validate_name, write_file_atomically, ResourceType},
+};
+use anyhow::{ensure, Context, Result};
+use serde_json::Value;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Gets the path to the file an arrangement is saved in
+///
+/// # Arguments
+///
+/// * `name` - The name of the arrangement (which should already be validated)
+pub fn get_window_data_path(name: &str) -> PathBuf {
+    get_data_dir().join(format!("{}.json", name))
+}
+
+/// Gets the names of the saved arrangements (going by their file names, without reading them), in alphabetical order
+///
+/// # Errors
+///
+/// Returns an error if the data directory could not be read
+pub fn list_window_data_names() -> Result<Vec<String>> {
+    let dir_path = get_data_dir();
+    if !resource_exists(&dir_path, ResourceType::Dir, false)? {
+        return Ok(Vec::new());
+    }
+
+    let mut names = Vec::new();
+    for entry in fs::read_dir(dir_path).with_context(|| "Failed to read data directory")? {
+        let path = entry
+            .with_context(|| "failed to get item from data directory")?
+            .path();
+        if !path.is_file()
+            || path.extension().and_then(|extension| extension.to_str()) != Some("json")
+        {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+            if validate_name(name).is_ok() {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+
+    Ok(names)
+}
+
+/// Gets the path to a backup of an arrangement, where backup 1 is the most recent
+fn get_backup_path(name: &str, number: usize) -> PathBuf {
+    get_data_dir()
+        .join("backup")
+        .join(format!("{}.{}.json", name, number))
+}
+
+/// Gets the paths to an arrangement's backups, most recent first
+fn get_backup_paths(name: &str) -> Vec<PathBuf> {
+    // Backups are numbered from 1 without gaps, as they are moved along together
+    (1..)
+        .map(|number| get_backup_path(name, number))
+        .take_while(|backup_path| backup_path.is_file())
+        .collect()
+}
+
+/// Moves an arrangement's backups to its new name, after it has been renamed, replacing any backups the new name already has
+///
+/// The name inside each backup is changed too, so that it can still be read in place of the arrangement. Backups that cannot be read are dropped
+///
+/// # Errors
+///
+/// Returns an error if a backup could not be written or removed
+pub fn move_backups(old_name: &str, new_name: &str) -> Result<()> {
+    remove_backups(new_name)?;
+
+    let mut number = 1;
+    for backup_path in get_backup_paths(old_name) {
+        if let Ok(mut window_data) = read_window_data_file(&backup_path, old_name) {
+            window_data.name = new_name.to_string();
+            let json_string = serde_json::to_string(&window_data)
+                .with_context(|| "Error formatting data as JSON string")?;
+            write_file_atomically(&get_backup_path(new_name, number), &json_string)
+                .with_context(|| "Failed to move backup")?;
+            number += 1;
+        }
+        fs::remove_file(&backup_path).with_context(|| "Failed to remove moved backup")?;
+    }
+
+    Ok(())
+}
+
+/// Removes all of an arrangement's backups
+///
+/// # Errors
+///
+/// Returns an error if a backup could not be removed
+pub fn remove_backups(name: &str) -> Result<()> {
+    for backup_path in get_backup_paths(name) {
+        fs::remove_file(backup_path).with_context(|| "Failed to remove backup")?;
+    }
+    Ok(())
+}
+
+/// Parses arrangement data from a JSON string, upgrading it to the current schema version if needed
+///
+/// # Errors
+///
+/// Returns an error if the string is not valid JSON, was written by a newer version of Windough, or does not match the arrangement schema
+pub fn parse_window_data(json_string: &str) -> Result<WindowData> {
+    let value: Value =
+        serde_json::from_str(json_string).with_context(|| "Error parsing file contents as JSON")?;
+    let value = migrate(value)?;
+    let window_data: WindowData =
+        serde_json::from_value(value).with_context(|| "Error reading arrangement data")?;
+
+    Ok(window_data)
+}
+
+/// Reads a saved arrangement, upgrading it to the current schema version if needed
+///
+/// If the arrangement's file cannot be read or parsed, its most recent readable backup is read instead (with a warning)
+///
+/// # Arguments
+///
+/// * `name` - The name of the arrangement (which should already be validated)
+///
+/// # Errors
+///
+/// Returns an error if the file does not exist, or if it and all of its backups cannot be read or parsed, or have a name that does not match
+pub fn read_window_data(name: &str) -> Result<WindowData> {
+    let file_path = get_window_data_path(name);
+    ensure!(
+        resource_exists(&file_path, ResourceType::File, false)?,
+        "File does not exist"
+    );
+
+    let error = match read_window_data_file(&file_path, name) {
+        Ok(window_data) => return Ok(window_data),
+        Err(error) => error,
+    };
+
+    let mut number = 1;
+    loop {
+        let backup_path = get_backup_path(name, number);
+        if !backup_path.is_file() {
+            return Err(error);
+        }
+        if let Ok(window_data) = read_window_data_file(&backup_path, name) {
+            printwarning!(
+                "{:#}",
+                error.context(format!(
+                    "arrangement '{}' could not be read - using its backup '{}' instead",
+                    name,
+                    backup_path.display()
+                ))
+            );
+            return Ok(window_data);
+        }
+        number += 1;
+    }
+}
+
+/// Reads and parses an arrangement file, checking that the name inside it matches
+fn read_window_data_file(file_path: &Path, name: &str) -> Result<WindowData> {
+    let json_string =
+        fs::read_to_string(file_path).with_context(|| "Error reading data from file")?;
+
+    let window_data = parse_window_data(&json_string)?;
+    ensure!(
+        window_data.name == name,
+        "'name' property in file does not match expected name"
+    );
+
+    Ok(window_data)
+}
+
+/// Writes an arrangement to its file in the data directory, at the current schema version
+///
+/// The file is replaced atomically (so it is never left half-written), and the version it replaces is kept as a backup
+///
+/// # Arguments
+///
+/// * `window_data` - The arrangement to write
+/// * `backup_count` - How many previous versions of the arrangement are kept as backups (`0` for none)
+///
+/// # Errors
+///
+/// Returns an error if the data directory could not be created, the previous version could not be backed up or the file could not be written
+pub fn write_window_data(window_data: &WindowData, backup_count: usize) -> Result<()> {
+    let json_string = serde_json::to_string(window_data)
+        .with_context(|| "Error formatting data as JSON string")?;
+
+    resource_exists(&get_data_dir(), ResourceType::Dir, true)?;
+    let file_path = get_window_data_path(&window_data.name);
+
+    back_up_window_data(&window_data.name, backup_count)
+        .with_context(|| "Failed to back up the previous version")?;
+    write_file_atomically(&file_path, &json_string)
+        .with_context(|| "Error writing data to file")?;
+
+    Ok(())
+}
+
+/// Copies an arrangement's file to its first backup, moving each older backup along by one and dropping those past `backup_count`
+///
+/// Nothing is backed up if the file does not exist or is not a valid arrangement, so a corrupt file never replaces a good backup
+fn back_up_window_data(name: &str, backup_count: usize) -> Result<()> {
+    if backup_count == 0 {
+        return Ok(());
+    }
+
+    let file_path = get_window_data_path(name);
+    if !file_path.is_file() || read_window_data_file(&file_path, name).is_err() {
+        return Ok(());
+    }
+
+    resource_exists(&get_data_dir().join("backup"), ResourceType::Dir, true)?;
+
+    let mut number = backup_count;
+    while get_backup_path(name, number).is_file() {
+        fs::remove_file(get_backup_path(name, number))?;
+        number += 1;
+    }
+    for number in (1..backup_count).rev() {
+        let backup_path = get_backup_path(name, number);
+        if backup_path.is_file() {
+            fs::rename(&backup_path, get_backup_path(name, number + 1))?;
+        }
+    }
+    fs::copy(&file_path, get_backup_path(name, 1))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn arrangement(description: &str) -> WindowData {
+        serde_json::from_value(json!({
+            "version": 2,
+            "name": "work",
+            "description": description,
+            "data": [],
+        }))
+        .unwrap()
+    }
+
+    fn read_description(file_path: &Path) -> String {
+        read_window_data_file(file_path, "work")
+            .unwrap()
+            .description
+            .unwrap()
+    }
+
+    #[test]
+    fn reads_backup_when_file_is_corrupt() {
+        write_window_data(&arrangement("first"), 3).unwrap();
+        write_window_data(&arrangement("second"), 3).unwrap();
+        fs::write(get_window_data_path("work"), "{").unwrap();
+
+        let window_data = read_window_data("work").unwrap();
+
+        assert_eq!(window_data.description.as_deref(), Some("first"));
+        fs::remove_dir_all(get_data_dir()).unwrap();
+    }
+
+    #[test]
+    fn reads_older_backup_when_newer_backup_is_corrupt() {
+        for description in ["first", "second", "third"] {
+            write_window_data(&arrangement(description), 3).unwrap();
+        }
+        fs::write(get_window_data_path("work"), "{").unwrap();
+        fs::write(get_backup_path("work", 1), "{").unwrap();
+
+        let window_data = read_window_data("work").unwrap();
+
+        assert_eq!(window_data.description.as_deref(), Some("first"));
+        fs::remove_dir_all(get_data_dir()).unwrap();
+    }
+
+    #[test]
+    fn fails_when_file_and_backups_are_corrupt() {
+        write_window_data(&arrangement("first"), 3).unwrap();
+        write_window_data(&arrangement("second"), 3).unwrap();
+        fs::write(get_window_data_path("work"), "{").unwrap();
+        fs::write(get_backup_path("work", 1), "{").unwrap();
+
+        let error = match read_window_data("work") {
+            Ok(_) => panic!("corrupt arrangement was read"),
+            Err(error) => error,
+        };
+
+        assert!(format!("{:#}", error).contains("Error parsing file contents as JSON"));
+        fs::remove_dir_all(get_data_dir()).unwrap();
+    }
+
+    #[test]
+    fn keeps_at_most_backup_count_backups() {
+        for description in ["first", "second", "third", "fourth", "fifth"] {
+            write_window_data(&arrangement(description), 2).unwrap();
+        }
+
+        assert_eq!(get_backup_paths("work").len(), 2);
+        assert_eq!(read_description(&get_backup_path("work", 1)), "fourth");
+        assert_eq!(read_description(&get_backup_path("work", 2)), "third");
+
+        // Lowering the count drops the backups past it on the next write
+        write_window_data(&arrangement("sixth"), 1).unwrap();
+
+        assert_eq!(get_backup_paths("work").len(), 1);
+        assert_eq!(read_description(&get_backup_path("work", 1)), "fifth");
+        fs::remove_dir_all(get_data_dir()).unwrap();
+    }
+
+    #[test]
+    fn does_not_back_up_corrupt_file() {
+        write_window_data(&arrangement("first"), 2).unwrap();
+        write_window_data(&arrangement("second"), 2).unwrap();
+        fs::write(get_window_data_path("work"), "{").unwrap();
+
+        write_window_data(&arrangement("third"), 2).unwrap();
+
+        assert_eq!(read_description(&get_backup_path("work", 1)), "first");
+        assert!(!get_backup_path("work", 2).is_file());
+        fs::remove_dir_all(get_data_dir()).unwrap();
+    }
+}
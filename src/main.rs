@@ -1,14 +1,21 @@
 mod commands;
 mod config;
 mod data;
+mod platform;
 #[macro_use]
 mod utils;
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use clap::{Parser, Subcommand};
-use config::{get_config, ConfigData};
+use commands::{
+    DiffTarget, EditOperation, EntryFields, EntrySelector, ListOptions, ListSort, LoadOptions,
+    SaveOptions, ShowFormat,
+};
+use config::{get_config, Backoff, ConfigData, OtherWindows, WindowFilter};
+use data::ReportFormat;
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
+use platform::get_window_system;
 use std::process::exit;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
@@ -17,7 +24,7 @@ use std::sync::{
 
 #[derive(Parser)]
 #[command(
-    about = "An easy-to-use command-line utility for saving and loading window arrangements on Windows",
+    about = "An easy-to-use command-line utility for saving and loading window arrangements on Windows and Linux",
     author = "SirGolem",
     version
 )]
@@ -35,6 +42,39 @@ enum Command {
     Save {
         /// Name to save arrangement as
         name: String,
+
+        #[arg(
+            long,
+            value_name = "FILTER",
+            help = "Only save windows that match this filter (e.g. 'exe=*\\code.exe,min-size=400x300'); can be repeated"
+        )]
+        include: Vec<WindowFilter>,
+        #[arg(
+            long,
+            value_name = "FILTER",
+            help = "Do not save windows that match this filter (e.g. 'title=Picture-in-picture'); can be repeated"
+        )]
+        exclude: Vec<WindowFilter>,
+
+        #[arg(
+            long,
+            help = "Describe the arrangement (replaces any existing description)"
+        )]
+        description: Option<String>,
+        #[arg(
+            long = "tag",
+            value_name = "TAG",
+            help = "Tag the arrangement (replaces any existing tags); can be repeated"
+        )]
+        tags: Vec<String>,
+
+        #[arg(
+            long,
+            short,
+            default_value_t = false,
+            help = "Overwrite the arrangement if it already exists, without asking"
+        )]
+        force: bool,
     },
     #[command(about = "Load a saved window arrangement")]
     Load {
@@ -44,28 +84,207 @@ enum Command {
         #[arg(
             long,
             default_value_t = false,
+            conflicts_with_all = ["minimize_others", "leave_others"],
             help = "Close windows that are not in the arrangement"
         )]
         close_others: bool,
         #[arg(
             long,
             default_value_t = false,
+            conflicts_with = "leave_others",
             help = "Minimize windows that are not in the arrangement"
         )]
         minimize_others: bool,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Leave windows that are not in the arrangement as they are (overrides the configuration and the arrangement's settings)"
+        )]
+        leave_others: bool,
+        #[arg(long, help = "Override the 'launch_concurrency' configuration value")]
+        launch_concurrency: Option<usize>,
 
         #[arg(long, help = "Override the 'retry_count' configuration value")]
         retry_count: Option<usize>,
         #[arg(long, help = "Override the 'retry_interval' configuration value")]
         retry_interval: Option<usize>,
+        #[arg(
+            long,
+            help = "Stop waiting for windows after this many milliseconds in total"
+        )]
+        timeout: Option<usize>,
+        #[arg(long, value_enum, help = "Override the 'backoff' configuration value")]
+        backoff: Option<Backoff>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Print what would be done without changing any windows"
+        )]
+        dry_run: bool,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = ReportFormat::Table,
+            help = "How to print the result of each entry"
+        )]
+        report: ReportFormat,
+    },
+    #[command(about = "Show the entries of a saved arrangement")]
+    Show {
+        /// Name of arrangement to show
+        name: String,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            conflicts_with = "toml",
+            help = "Print the arrangement as JSON"
+        )]
+        json: bool,
+        #[arg(long, default_value_t = false, help = "Print the arrangement as TOML")]
+        toml: bool,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Check that each entry's executable still exists"
+        )]
+        check: bool,
     },
-    #[command(about = "Remove a saved window arrangement")]
+    #[command(about = "Restore the arrangement of windows from before the last load")]
+    Undo,
+    #[command(about = "Remove saved window arrangements")]
     Remove {
-        /// Name of arrangement to remove
+        /// Names of arrangements to remove, or glob patterns matching them (e.g. 'work-*' - quote patterns so the shell does not expand them)
+        #[arg(required = true)]
+        names: Vec<String>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Print the arrangements that would be removed, without removing them"
+        )]
+        dry_run: bool,
+        #[arg(
+            long,
+            short,
+            default_value_t = false,
+            help = "Remove several arrangements without asking"
+        )]
+        force: bool,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Keep the revision history of the arrangements, so they can be restored later"
+        )]
+        keep_history: bool,
+    },
+    #[command(about = "Rename a saved window arrangement")]
+    Rename {
+        /// Current name of arrangement
+        old_name: String,
+        /// New name of arrangement
+        new_name: String,
+
+        #[arg(
+            long,
+            short,
+            default_value_t = false,
+            help = "Overwrite an arrangement with the new name if there is one, without asking"
+        )]
+        force: bool,
+    },
+    #[command(about = "Copy a saved window arrangement to a new name")]
+    Copy {
+        /// Name of arrangement to copy
+        source: String,
+        /// Name to save the copy as
+        destination: String,
+
+        #[arg(
+            long,
+            short,
+            default_value_t = false,
+            help = "Overwrite an arrangement with the destination name if there is one, without asking"
+        )]
+        force: bool,
+    },
+    #[command(about = "Compare an arrangement with another one, or with the open windows")]
+    Diff {
+        /// Name of arrangement to compare
+        name: String,
+        /// Name of arrangement to compare it with (if not given, it is compared with the open windows - minimized windows are left minimized, so only their state is compared)
+        other: Option<String>,
+
+        #[arg(long, default_value_t = false, help = "Print the differences as JSON")]
+        json: bool,
+    },
+    #[command(about = "Change a saved arrangement")]
+    Edit {
+        /// Name of arrangement to edit
+        name: String,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Open the arrangement in your editor ($VISUAL or $EDITOR), and check it before saving"
+        )]
+        editor: bool,
+
+        #[command(subcommand)]
+        action: Option<EditAction>,
+    },
+    #[command(about = "List the saved revisions of an arrangement")]
+    History {
+        /// Name of arrangement
         name: String,
     },
+    #[command(about = "Restore an arrangement to one of its saved revisions")]
+    Restore {
+        /// Name of arrangement to restore
+        name: String,
+
+        #[arg(
+            long,
+            value_name = "N",
+            help = "The revision to restore (see 'windough history')"
+        )]
+        revision: usize,
+    },
     #[command(about = "List saved arrangements")]
-    List,
+    List {
+        #[arg(
+            short,
+            long,
+            default_value_t = false,
+            help = "Show the entry count, times, tags and description of each arrangement"
+        )]
+        long: bool,
+        #[arg(long, value_enum, default_value_t = ListSort::Name, help = "How to sort arrangements")]
+        sort: ListSort,
+        #[arg(long, default_value_t = false, help = "Print the list as JSON")]
+        json: bool,
+        #[arg(
+            long = "tag",
+            value_name = "TAG",
+            help = "Only list arrangements with this tag; can be repeated"
+        )]
+        tags: Vec<String>,
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "List files that are not valid arrangements, and why"
+        )]
+        broken: bool,
+    },
+    #[command(about = "Upgrade all saved arrangements to the current file format")]
+    Migrate,
+    #[command(about = "Show or change configuration values")]
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
     #[command(about = "Clear Windough data (default - saved arrangements)")]
     Clear {
         #[arg(
@@ -76,7 +295,7 @@ enum Command {
         )]
         all: bool,
     },
-    #[command(about = "Open a directory in File Explorer")]
+    #[command(about = "Open a directory in the file manager")]
     OpenDir {
         #[arg(
             short,
@@ -102,6 +321,86 @@ enum Command {
     },
 }
 
+#[derive(Subcommand)]
+enum ConfigAction {
+    #[command(about = "Print the value of a configuration key")]
+    Get {
+        /// Configuration key to print
+        key: String,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Also print where the value was set"
+        )]
+        show_origin: bool,
+    },
+    #[command(about = "Set a configuration key in the user config file")]
+    Set {
+        /// Configuration key to set
+        key: String,
+        /// Value to set (read as JSON if possible, otherwise as a string)
+        value: String,
+    },
+    #[command(about = "Remove a configuration key from the user config file")]
+    Unset {
+        /// Configuration key to remove
+        key: String,
+    },
+    #[command(about = "Print every configuration key and its value")]
+    List {
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Also print where each value was set"
+        )]
+        show_origin: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum EditAction {
+    #[command(about = "List, change, add, remove or reorder the arrangement's entries")]
+    Entry {
+        #[command(subcommand)]
+        action: EntryAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum EntryAction {
+    #[command(about = "List the arrangement's entries")]
+    List,
+    #[command(about = "Change an entry")]
+    Set {
+        /// Index of the entry, or the name or path of its application
+        entry: EntrySelector,
+
+        #[command(flatten)]
+        fields: EntryFields,
+    },
+    #[command(about = "Add an entry (--geometry is required)")]
+    Add {
+        /// Path of the application's executable
+        application_path: String,
+
+        #[command(flatten)]
+        fields: EntryFields,
+    },
+    #[command(about = "Remove an entry")]
+    Remove {
+        /// Index of the entry, or the name or path of its application
+        entry: EntrySelector,
+    },
+    #[command(about = "Move an entry to another index")]
+    Move {
+        /// Index of the entry, or the name or path of its application
+        entry: EntrySelector,
+        /// Index to move the entry to
+        to: usize,
+    },
+}
+
 lazy_static! {
     static ref PROJECT_DIRS: Arc<ProjectDirs> = Arc::new(
         ProjectDirs::from(
@@ -134,36 +433,170 @@ pub fn verbose() -> bool {
 }
 
 fn main() {
-    if !cfg!(windows) {
-        printerror!("cannot run on this OS - only Windows is supported");
-        return;
-    }
-
     let args = Args::parse();
     VERBOSE.store(args.verbose, Ordering::Relaxed);
 
     let command_result = match args.command {
-        Command::Save { name } => {
-            commands::save(name).with_context(|| "error saving window arragement")
-        }
+        Command::Save {
+            name,
+            include,
+            exclude,
+            description,
+            tags,
+            force,
+        } => get_window_system()
+            .and_then(|window_system| {
+                commands::save(
+                    &*window_system,
+                    name,
+                    SaveOptions {
+                        include,
+                        exclude,
+                        description,
+                        tags,
+                        force,
+                    },
+                )
+            })
+            .with_context(|| "error saving window arragement"),
         Command::Load {
             name,
             close_others,
             minimize_others,
+            leave_others,
+            launch_concurrency,
             retry_count,
             retry_interval,
-        } => commands::load(
+            timeout,
+            backoff,
+            dry_run,
+            report,
+        } => get_window_system()
+            .and_then(|window_system| {
+                commands::load(
+                    &*window_system,
+                    name,
+                    LoadOptions {
+                        other_windows: if close_others {
+                            Some(OtherWindows::Close)
+                        } else if minimize_others {
+                            Some(OtherWindows::Minimize)
+                        } else if leave_others {
+                            Some(OtherWindows::Leave)
+                        } else {
+                            None
+                        },
+                        launch_concurrency,
+                        retry_count,
+                        retry_interval,
+                        timeout,
+                        backoff,
+                        dry_run,
+                        report_format: report,
+                    },
+                )
+            })
+            .with_context(|| "error loading window arrangement"),
+        Command::Show {
             name,
-            close_others,
-            minimize_others,
-            retry_count,
-            retry_interval,
-        )
-        .with_context(|| "error loading window arrangement"),
-        Command::Remove { name } => {
-            commands::remove(name).with_context(|| "error removing window arrangement")
+            json,
+            toml,
+            check,
+        } => {
+            let format = if json {
+                ShowFormat::Json
+            } else if toml {
+                ShowFormat::Toml
+            } else {
+                ShowFormat::Table
+            };
+            commands::show(name, format, check).with_context(|| "error showing arrangement")
         }
-        Command::List => commands::list().with_context(|| "error listing saved arrangements"),
+        Command::Undo => get_window_system()
+            .and_then(|window_system| commands::undo(&*window_system))
+            .with_context(|| "error undoing load"),
+        Command::Remove {
+            names,
+            dry_run,
+            force,
+            keep_history,
+        } => commands::remove(names, dry_run, force, keep_history)
+            .with_context(|| "error removing window arrangements"),
+        Command::Rename {
+            old_name,
+            new_name,
+            force,
+        } => commands::rename(old_name, new_name, force)
+            .with_context(|| "error renaming window arrangement"),
+        Command::Copy {
+            source,
+            destination,
+            force,
+        } => commands::copy(source, destination, force)
+            .with_context(|| "error copying window arrangement"),
+        Command::Diff { name, other, json } => match other {
+            Some(other) => commands::diff(name, DiffTarget::Arrangement(other), json),
+            None => get_window_system().and_then(|window_system| {
+                commands::diff(name, DiffTarget::Desktop(&*window_system), json)
+            }),
+        }
+        .with_context(|| "error comparing arrangements"),
+        Command::Edit {
+            name,
+            editor,
+            action,
+        } => {
+            let operation = match (editor, action) {
+                (true, None) => Ok(EditOperation::Editor),
+                (true, Some(_)) => Err(anyhow!(
+                    "--editor cannot be used with 'entry' (edit the entries in the editor instead)"
+                )),
+                (false, Some(EditAction::Entry { action })) => Ok(match action {
+                    EntryAction::List => EditOperation::ListEntries,
+                    EntryAction::Set { entry, fields } => EditOperation::SetEntry(entry, fields),
+                    EntryAction::Add {
+                        application_path,
+                        fields,
+                    } => EditOperation::AddEntry(application_path, fields),
+                    EntryAction::Remove { entry } => EditOperation::RemoveEntry(entry),
+                    EntryAction::Move { entry, to } => EditOperation::MoveEntry(entry, to),
+                }),
+                (false, None) => Err(anyhow!(
+                    "Nothing to edit - use --editor or 'windough edit <name> entry ...'"
+                )),
+            };
+            operation
+                .and_then(|operation| commands::edit(name, operation))
+                .with_context(|| "error editing arrangement")
+        }
+        Command::History { name } => {
+            commands::history(name).with_context(|| "error reading arrangement history")
+        }
+        Command::Restore { name, revision } => {
+            commands::restore(name, revision).with_context(|| "error restoring arrangement")
+        }
+        Command::List {
+            long,
+            sort,
+            json,
+            tags,
+            broken,
+        } => commands::list(ListOptions {
+            long,
+            sort,
+            json,
+            tags,
+            broken,
+        })
+        .with_context(|| "error listing saved arrangements"),
+        Command::Migrate => commands::migrate().with_context(|| "error migrating arrangements"),
+        Command::Config { action } => match action {
+            ConfigAction::Get { key, show_origin } => commands::config_get(key, show_origin),
+            ConfigAction::Set { key, value } => commands::config_set(key, value),
+            ConfigAction::Unset { key } => commands::config_unset(key),
+            ConfigAction::List { show_origin } => commands::config_list(show_origin),
+        }
+        .with_context(|| "error accessing configuration"),
         Command::Clear { all } => commands::clear(all).with_context(|| "error clearing data"),
         Command::OpenDir { root, data, config } => {
             commands::open_dir(root, data, config).with_context(|| "error opening directory")
@@ -180,6 +613,7 @@ fn main() {
                     None => printerror!("{}", error),
                 }
             }
+            exit(1);
         }
     }
 }
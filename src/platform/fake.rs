@@ -0,0 +1,356 @@
+use super::{CommandLine, WindowId, WindowSystem};
+use crate::data::{MonitorData, WindowPosition, WindowSize};
+use anyhow::{bail, Result};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// A window tracked by `FakeWindowSystem`
+#[derive(Clone, Debug)]
+pub struct FakeWindow {
+    pub module_path: String,
+    pub title: String,
+    pub class: Option<String>,
+    pub position: WindowPosition,
+    pub size: WindowSize,
+    pub minimized: bool,
+    pub maximized: bool,
+    pub command_line: Option<CommandLine>,
+    /// The smallest size the window accepts, as some applications enforce
+    pub min_size: Option<WindowSize>,
+}
+
+impl FakeWindow {
+    pub fn new(module_path: &str, title: &str, position: WindowPosition, size: WindowSize) -> Self {
+        FakeWindow {
+            module_path: module_path.to_string(),
+            title: title.to_string(),
+            class: None,
+            position,
+            size,
+            minimized: false,
+            maximized: false,
+            command_line: None,
+            min_size: None,
+        }
+    }
+}
+
+/// An application launch requested from `FakeWindowSystem`
+#[derive(Clone, Debug)]
+pub struct FakeLaunch {
+    pub application_path: String,
+    pub application_args: Vec<String>,
+    pub working_directory: Option<String>,
+}
+
+enum FakeEvent {
+    Open(WindowId, FakeWindow),
+    Move(WindowId, WindowPosition, WindowSize),
+    Retitle(WindowId, String),
+    Close(WindowId),
+}
+
+#[derive(Default)]
+struct FakeState {
+    now: Duration,
+    next_id: u64,
+    windows: Vec<(WindowId, FakeWindow)>,
+    scheduled: Vec<(Duration, FakeEvent)>,
+    launch_scripts: HashMap<String, (Duration, FakeWindow)>,
+    launched: Vec<FakeLaunch>,
+    monitors: Vec<MonitorData>,
+    closed: Vec<WindowId>,
+}
+
+impl FakeState {
+    fn allocate_id(&mut self) -> WindowId {
+        self.next_id += 1;
+        WindowId(self.next_id)
+    }
+
+    fn schedule(&mut self, delay: Duration, event: FakeEvent) {
+        let at = self.now + delay;
+        if delay.is_zero() {
+            self.apply(event);
+        } else {
+            self.scheduled.push((at, event));
+        }
+    }
+
+    fn apply(&mut self, event: FakeEvent) {
+        match event {
+            FakeEvent::Open(id, window) => self.windows.push((id, window)),
+            FakeEvent::Move(id, position, size) => {
+                if let Some((_, window)) = self.windows.iter_mut().find(|(other, _)| *other == id) {
+                    window.position = position;
+                    window.size = size;
+                }
+            }
+            FakeEvent::Retitle(id, title) => {
+                if let Some((_, window)) = self.windows.iter_mut().find(|(other, _)| *other == id) {
+                    window.title = title;
+                }
+            }
+            FakeEvent::Close(id) => self.windows.retain(|(other, _)| *other != id),
+        }
+    }
+
+    fn advance(&mut self, duration: Duration) {
+        self.now += duration;
+
+        let (mut due, pending): (Vec<_>, Vec<_>) = self
+            .scheduled
+            .drain(..)
+            .partition(|(at, _)| *at <= self.now);
+        self.scheduled = pending;
+
+        due.sort_by_key(|(at, _)| *at);
+        for (_, event) in due {
+            self.apply(event);
+        }
+    }
+
+    fn window_mut(&mut self, id: WindowId) -> Result<&mut FakeWindow> {
+        match self.windows.iter_mut().find(|(other, _)| *other == id) {
+            Some((_, window)) => Ok(window),
+            None => bail!("Window {} does not exist", id.0),
+        }
+    }
+}
+
+/// An in-memory window system that never touches a real desktop
+///
+/// Windows can be scripted to appear, move and change title at points on a virtual clock, which only advances when `sleep` is called, so the load wait loop runs instantly and deterministically
+pub struct FakeWindowSystem {
+    state: RefCell<FakeState>,
+    /// The real time the virtual clock started at, so that `now` can return an `Instant`
+    epoch: Instant,
+}
+
+impl Default for FakeWindowSystem {
+    fn default() -> Self {
+        FakeWindowSystem {
+            state: RefCell::default(),
+            epoch: Instant::now(),
+        }
+    }
+}
+
+impl FakeWindowSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens a window immediately
+    pub fn open_window(&self, window: FakeWindow) -> WindowId {
+        self.open_window_after(Duration::ZERO, window)
+    }
+
+    /// Opens a window once the virtual clock has advanced by `delay`
+    pub fn open_window_after(&self, delay: Duration, window: FakeWindow) -> WindowId {
+        let mut state = self.state.borrow_mut();
+        let id = state.allocate_id();
+        state.schedule(delay, FakeEvent::Open(id, window));
+        id
+    }
+
+    /// Moves and resizes a window once the virtual clock has advanced by `delay`, as if the application did it itself
+    pub fn move_window_after(
+        &self,
+        delay: Duration,
+        id: WindowId,
+        position: WindowPosition,
+        size: WindowSize,
+    ) {
+        self.state
+            .borrow_mut()
+            .schedule(delay, FakeEvent::Move(id, position, size));
+    }
+
+    /// Changes a window's title once the virtual clock has advanced by `delay`, as if the application did it itself
+    pub fn retitle_window_after(&self, delay: Duration, id: WindowId, title: &str) {
+        self.state
+            .borrow_mut()
+            .schedule(delay, FakeEvent::Retitle(id, title.to_string()));
+    }
+
+    /// Makes launching `application_path` open `window` after `delay`
+    pub fn on_launch(&self, application_path: &str, delay: Duration, window: FakeWindow) {
+        self.state
+            .borrow_mut()
+            .launch_scripts
+            .insert(application_path.to_string(), (delay, window));
+    }
+
+    /// Sets the monitors that are connected
+    pub fn set_monitors(&self, monitors: Vec<MonitorData>) {
+        self.state.borrow_mut().monitors = monitors;
+    }
+
+    /// Gets the current state of a window, or `None` if it is not open
+    pub fn window(&self, id: WindowId) -> Option<FakeWindow> {
+        self.state
+            .borrow()
+            .windows
+            .iter()
+            .find(|(other, _)| *other == id)
+            .map(|(_, window)| window.clone())
+    }
+
+    /// Gets every application launch requested so far, in order
+    pub fn launched(&self) -> Vec<FakeLaunch> {
+        self.state.borrow().launched.clone()
+    }
+
+    /// Gets every window closed through `WindowSystem::close` so far, in order
+    pub fn closed(&self) -> Vec<WindowId> {
+        self.state.borrow().closed.clone()
+    }
+
+    /// Gets how far the virtual clock has advanced
+    pub fn elapsed(&self) -> Duration {
+        self.state.borrow().now
+    }
+}
+
+impl WindowSystem for FakeWindowSystem {
+    fn get_open_windows(&self) -> Result<Vec<WindowId>> {
+        Ok(self
+            .state
+            .borrow()
+            .windows
+            .iter()
+            .map(|(id, _)| *id)
+            .collect())
+    }
+
+    fn get_module_path(&self, window: WindowId) -> Result<String> {
+        Ok(self
+            .state
+            .borrow_mut()
+            .window_mut(window)?
+            .module_path
+            .clone())
+    }
+
+    fn get_title(&self, window: WindowId) -> Result<String> {
+        Ok(self.state.borrow_mut().window_mut(window)?.title.clone())
+    }
+
+    fn get_class(&self, window: WindowId) -> Result<Option<String>> {
+        Ok(self.state.borrow_mut().window_mut(window)?.class.clone())
+    }
+
+    fn get_command_line(&self, window: WindowId) -> Result<Option<CommandLine>> {
+        Ok(self
+            .state
+            .borrow_mut()
+            .window_mut(window)?
+            .command_line
+            .clone())
+    }
+
+    fn get_monitors(&self) -> Result<Vec<MonitorData>> {
+        Ok(self.state.borrow().monitors.clone())
+    }
+
+    fn get_rect(&self, window: WindowId) -> Result<(WindowPosition, WindowSize)> {
+        let mut state = self.state.borrow_mut();
+        let window = state.window_mut(window)?;
+        Ok((window.position, window.size))
+    }
+
+    fn is_minimized(&self, window: WindowId) -> Result<bool> {
+        Ok(self.state.borrow_mut().window_mut(window)?.minimized)
+    }
+
+    fn is_maximized(&self, window: WindowId) -> Result<bool> {
+        Ok(self.state.borrow_mut().window_mut(window)?.maximized)
+    }
+
+    fn reposition_and_resize(
+        &self,
+        window: WindowId,
+        position: &WindowPosition,
+        size: &WindowSize,
+    ) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        let window = state.window_mut(window)?;
+        window.position = *position;
+        window.size = match window.min_size {
+            Some(min_size) => WindowSize {
+                width: size.width.max(min_size.width),
+                height: size.height.max(min_size.height),
+            },
+            None => *size,
+        };
+        Ok(())
+    }
+
+    fn restore(&self, window: WindowId) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        let window = state.window_mut(window)?;
+        if window.minimized {
+            window.minimized = false;
+        } else {
+            window.maximized = false;
+        }
+        Ok(())
+    }
+
+    fn minimize(&self, window: WindowId) -> Result<()> {
+        self.state.borrow_mut().window_mut(window)?.minimized = true;
+        Ok(())
+    }
+
+    fn maximize(&self, window: WindowId) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        let window = state.window_mut(window)?;
+        window.minimized = false;
+        window.maximized = true;
+        Ok(())
+    }
+
+    fn close(&self, window: WindowId) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        state.window_mut(window)?;
+        state.apply(FakeEvent::Close(window));
+        state.closed.push(window);
+        Ok(())
+    }
+
+    fn launch_application(
+        &self,
+        application_path: &str,
+        application_args: &[String],
+        working_directory: Option<&str>,
+    ) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        state.launched.push(FakeLaunch {
+            application_path: application_path.to_string(),
+            application_args: application_args.to_vec(),
+            working_directory: working_directory.map(|directory| directory.to_string()),
+        });
+
+        let script = state.launch_scripts.get(application_path).cloned();
+        match script {
+            Some((delay, window)) => {
+                let id = state.allocate_id();
+                state.schedule(delay, FakeEvent::Open(id, window));
+                Ok(())
+            }
+            None => bail!("Failed to launch application"),
+        }
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.state.borrow_mut().advance(duration);
+    }
+
+    fn now(&self) -> Instant {
+        self.epoch + self.state.borrow().now
+    }
+}
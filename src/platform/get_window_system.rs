@@ -0,0 +1,48 @@
+use super::WindowSystem;
+use anyhow::Result;
+
+/// Gets the window system backend for the current platform
+///
+/// # Errors
+///
+/// Returns an error if there is no backend for the current platform, or the backend fails to connect
+#[cfg(windows)]
+pub fn get_window_system() -> Result<Box<dyn WindowSystem>> {
+    Ok(Box::new(super::WindowsWindowSystem))
+}
+
+/// Gets the window system backend for the current platform
+///
+/// # Errors
+///
+/// Returns an error if there is no backend for the current platform, or the backend fails to connect
+#[cfg(target_os = "linux")]
+pub fn get_window_system() -> Result<Box<dyn WindowSystem>> {
+    use anyhow::{ensure, Context};
+    use std::{env, path::Path};
+
+    // Tiling window managers own the layout, so their IPC socket takes priority over the X server
+    if let Some(socket_path) = env::var_os("SWAYSOCK").or_else(|| env::var_os("I3SOCK")) {
+        let window_system = super::I3WindowSystem::connect(Path::new(&socket_path))
+            .with_context(|| "Failed to start i3/sway backend")?;
+        return Ok(Box::new(window_system));
+    }
+
+    ensure!(
+        env::var_os("DISPLAY").is_some(),
+        "No window system found - set the DISPLAY, SWAYSOCK or I3SOCK environment variable"
+    );
+    let window_system =
+        super::X11WindowSystem::connect().with_context(|| "Failed to start X11 backend")?;
+    Ok(Box::new(window_system))
+}
+
+/// Gets the window system backend for the current platform
+///
+/// # Errors
+///
+/// Returns an error if there is no backend for the current platform, or the backend fails to connect
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn get_window_system() -> Result<Box<dyn WindowSystem>> {
+    anyhow::bail!("Cannot run on this OS - only Windows and Linux are supported")
+}
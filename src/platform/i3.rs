@@ -0,0 +1,833 @@
+use super::{procfs, CommandLine, WindowId, WindowSystem, X11WindowSystem};
+use crate::{
+    data::{MonitorData, ScreenRect, WindowPosition, WindowSize, WindowWorkspace},
+    printwarning, verbose,
+};
+use anyhow::{bail, ensure, Context, Result};
+use serde_json::Value;
+use std::{
+    cell::RefCell,
+    env,
+    io::{self, Read, Write},
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+const MAGIC: &[u8; 6] = b"i3-ipc";
+const RUN_COMMAND: u32 = 0;
+const GET_WORKSPACES: u32 = 1;
+const SUBSCRIBE: u32 = 2;
+const GET_OUTPUTS: u32 = 3;
+const GET_TREE: u32 = 4;
+const SCRATCHPAD_WORKSPACE: &str = "__i3_scratch";
+// How long the window manager has to accept a subscription before events are polled for instead
+const SUBSCRIBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The details of a window container that Windough cares about, as found in the `GET_TREE` reply
+#[derive(Clone)]
+struct Container {
+    id: u64,
+    pid: Option<u32>,
+    x11_window: Option<u32>,
+    title: String,
+    class: Option<String>,
+    workspace: String,
+    floating: bool,
+    fullscreen: bool,
+    rect: (WindowPosition, WindowSize),
+}
+
+/// An i3 or sway window system, driven through the window manager's IPC socket
+///
+/// Windows are identified by container ID, and layout is changed with IPC commands rather than by moving windows directly
+pub struct I3WindowSystem {
+    socket_path: PathBuf,
+    stream: RefCell<UnixStream>,
+    /// A second connection subscribed to window events, opened the first time one is waited for
+    event_stream: RefCell<Option<UnixStream>>,
+    /// The containers from the last `GET_TREE`, looked up until the layout may have changed (when a command is run or time passes), so each look at the open windows fetches the tree once
+    containers: RefCell<Option<Vec<Container>>>,
+    x11: Option<X11WindowSystem>,
+}
+
+impl I3WindowSystem {
+    /// Connects to the IPC socket at a path (usually from the `SWAYSOCK` or `I3SOCK` environment variable)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket could not be connected to
+    pub fn connect(socket_path: &Path) -> Result<Self> {
+        let stream = UnixStream::connect(socket_path).with_context(|| {
+            format!("Failed to connect to IPC socket {}", socket_path.display())
+        })?;
+
+        // i3 does not report process IDs, so they are read from the X11 windows instead (when there is a display)
+        let x11 = match env::var_os("DISPLAY") {
+            Some(_) => X11WindowSystem::connect().ok(),
+            None => None,
+        };
+
+        Ok(I3WindowSystem {
+            socket_path: socket_path.to_path_buf(),
+            stream: RefCell::new(stream),
+            event_stream: RefCell::new(None),
+            containers: RefCell::new(None),
+            x11,
+        })
+    }
+
+    fn request(&self, message_type: u32, payload: &str) -> Result<Value> {
+        let mut stream = self.stream.borrow_mut();
+        send_message(&mut stream, message_type, payload)?;
+
+        let (reply_type, body) = read_message(&mut *stream)?;
+        ensure!(
+            reply_type == message_type,
+            "Unexpected IPC reply type {} (expected {})",
+            reply_type,
+            message_type
+        );
+        serde_json::from_slice(&body).with_context(|| "Error parsing IPC reply as JSON")
+    }
+
+    /// Opens a second connection to the IPC socket, subscribed to window events
+    fn subscribe_to_window_events(&self) -> Result<UnixStream> {
+        let mut stream = UnixStream::connect(&self.socket_path).with_context(|| {
+            format!(
+                "Failed to connect to IPC socket {}",
+                self.socket_path.display()
+            )
+        })?;
+        send_message(&mut stream, SUBSCRIBE, "[\"window\"]")?;
+        stream.set_read_timeout(Some(SUBSCRIBE_TIMEOUT))?;
+
+        let (reply_type, body) = read_message(&mut stream)?;
+        let reply: Value =
+            serde_json::from_slice(&body).with_context(|| "Error parsing IPC reply as JSON")?;
+        ensure!(
+            reply_type == SUBSCRIBE && reply["success"] == true,
+            "Window manager rejected subscription to window events"
+        );
+
+        Ok(stream)
+    }
+
+    /// Waits for a window event on the subscribed connection, returning whether one arrived before the timeout
+    fn read_window_event(&self, timeout: Duration) -> Result<bool> {
+        let mut event_stream = self.event_stream.borrow_mut();
+        if event_stream.is_none() {
+            *event_stream = Some(self.subscribe_to_window_events()?);
+        }
+        let stream = match event_stream.as_mut() {
+            Some(data) => data,
+            None => return Ok(false),
+        };
+
+        // Only the first byte is read with a timeout, so a timeout never leaves a message half read
+        stream.set_read_timeout(Some(timeout.max(Duration::from_millis(1))))?;
+        let mut first_byte = [0u8; 1];
+        let result = match stream.read_exact(&mut first_byte) {
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                return Ok(false)
+            }
+            Err(error) => Err(error.into()),
+            Ok(_) => stream
+                .set_read_timeout(None)
+                .map_err(|error| error.into())
+                .and_then(|_| read_message(&mut first_byte.chain(&mut *stream))),
+        };
+        if let Err(error) = result {
+            *event_stream = None;
+            return Err(error);
+        }
+
+        Ok(true)
+    }
+
+    fn run_command(&self, window: WindowId, command: &str) -> Result<()> {
+        self.forget_containers();
+        let reply = self.request(RUN_COMMAND, &format!("[con_id={}] {}", window.0, command))?;
+        for outcome in reply.as_array().into_iter().flatten() {
+            if outcome["success"] != Value::Bool(true) {
+                bail!(
+                    "Window manager rejected command '{}': {}",
+                    command,
+                    outcome["error"].as_str().unwrap_or("unknown error")
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn get_pid(&self, window: WindowId) -> Result<u32> {
+        let container = self.get_container(window)?;
+        match (container.pid, container.x11_window, &self.x11) {
+            (Some(pid), _, _) => Ok(pid),
+            (None, Some(x11_window), Some(x11)) => x11.get_pid(WindowId(x11_window as u64)),
+            _ => bail!("Container {} has no known process ID", window.0),
+        }
+    }
+
+    /// Fetches the layout tree, keeping its containers for `get_container`
+    fn refresh_containers(&self) -> Result<()> {
+        let tree = self.request(GET_TREE, "")?;
+        let mut containers = Vec::new();
+        collect_containers(&tree, None, false, &mut containers);
+        *self.containers.borrow_mut() = Some(containers);
+        Ok(())
+    }
+
+    fn forget_containers(&self) {
+        *self.containers.borrow_mut() = None;
+    }
+
+    fn get_container(&self, window: WindowId) -> Result<Container> {
+        if self.containers.borrow().is_none() {
+            self.refresh_containers()?;
+        }
+        match self
+            .containers
+            .borrow()
+            .iter()
+            .flatten()
+            .find(|container| container.id == window.0)
+        {
+            Some(container) => Ok(container.clone()),
+            None => bail!("Container {} does not exist", window.0),
+        }
+    }
+}
+
+/// Sends a message over an IPC connection
+fn send_message(stream: &mut UnixStream, message_type: u32, payload: &str) -> Result<()> {
+    let mut message = Vec::with_capacity(14 + payload.len());
+    message.extend_from_slice(MAGIC);
+    message.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+    message.extend_from_slice(&message_type.to_ne_bytes());
+    message.extend_from_slice(payload.as_bytes());
+    stream
+        .write_all(&message)
+        .with_context(|| "Failed to send IPC message")
+}
+
+/// Reads a reply or event from an IPC connection, returning its type and body
+fn read_message(stream: &mut impl Read) -> Result<(u32, Vec<u8>)> {
+    let mut header = [0u8; 14];
+    stream
+        .read_exact(&mut header)
+        .with_context(|| "Failed to read IPC reply header")?;
+    ensure!(&header[0..6] == MAGIC, "Invalid IPC reply header");
+    let length = u32::from_ne_bytes([header[6], header[7], header[8], header[9]]) as usize;
+    let message_type = u32::from_ne_bytes([header[10], header[11], header[12], header[13]]);
+
+    let mut body = vec![0u8; length];
+    stream
+        .read_exact(&mut body)
+        .with_context(|| "Failed to read IPC reply body")?;
+
+    Ok((message_type, body))
+}
+
+/// Walks the layout tree, collecting every container that holds a window
+///
+/// # Arguments
+///
+/// * `node` - The node to walk from
+/// * `workspace` - The name of the workspace `node` is on, if known yet
+/// * `in_floating_con` - Whether `node` is inside an i3 floating container
+/// * `containers` - The vector to add window containers to
+fn collect_containers(
+    node: &Value,
+    workspace: Option<&str>,
+    in_floating_con: bool,
+    containers: &mut Vec<Container>,
+) {
+    let node_type = node["type"].as_str().unwrap_or_default();
+    let workspace = if node_type == "workspace" {
+        node["name"].as_str()
+    } else {
+        workspace
+    };
+
+    let floating = in_floating_con || node_type == "floating_con";
+
+    let x11_window = node["window"].as_u64().map(|window| window as u32);
+    let pid = node["pid"].as_u64().map(|pid| pid as u32);
+    if let (Some(workspace), Some(id)) = (workspace, node["id"].as_u64()) {
+        if x11_window.is_some() || pid.is_some() {
+            let class = match node["app_id"].as_str() {
+                Some(app_id) => Some(app_id.to_string()),
+                None => node["window_properties"]["class"]
+                    .as_str()
+                    .map(|class| class.to_string()),
+            };
+
+            containers.push(Container {
+                id,
+                pid,
+                x11_window,
+                title: node["name"].as_str().unwrap_or_default().to_string(),
+                class,
+                workspace: workspace.to_string(),
+                floating,
+                fullscreen: node["fullscreen_mode"].as_u64().unwrap_or_default() != 0,
+                rect: get_rect(&node["rect"]),
+            });
+        }
+    }
+
+    // i3 keeps floating windows inside a "floating_con" wrapper, whose rect is what "move position" and "resize set" act on
+    for child in node["nodes"].as_array().into_iter().flatten() {
+        collect_containers(child, workspace, floating, containers);
+    }
+    for child in node["floating_nodes"].as_array().into_iter().flatten() {
+        let before = containers.len();
+        collect_containers(child, workspace, true, containers);
+        if child["type"] == "floating_con" && containers.len() == before + 1 {
+            containers[before].rect = get_rect(&child["rect"]);
+        }
+    }
+}
+
+fn get_screen_rect(rect: &Value) -> ScreenRect {
+    let (position, size) = get_rect(rect);
+    ScreenRect {
+        left: position.left,
+        top: position.top,
+        width: size.width,
+        height: size.height,
+    }
+}
+
+fn get_rect(rect: &Value) -> (WindowPosition, WindowSize) {
+    let field = |name: &str| rect[name].as_i64().unwrap_or_default() as i32;
+    (
+        WindowPosition {
+            top: field("y"),
+            left: field("x"),
+        },
+        WindowSize {
+            width: field("width"),
+            height: field("height"),
+        },
+    )
+}
+
+/// Quotes a string for use as an argument in an i3 command
+fn quote(argument: &str) -> String {
+    format!(
+        "\"{}\"",
+        argument.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+impl WindowSystem for I3WindowSystem {
+    fn get_open_windows(&self) -> Result<Vec<WindowId>> {
+        // Each look at the open windows starts from a fresh tree
+        self.refresh_containers()?;
+        Ok(self
+            .containers
+            .borrow()
+            .iter()
+            .flatten()
+            .map(|container| WindowId(container.id))
+            .collect())
+    }
+
+    fn get_module_path(&self, window: WindowId) -> Result<String> {
+        procfs::get_module_path(self.get_pid(window)?)
+    }
+
+    fn get_command_line(&self, window: WindowId) -> Result<Option<CommandLine>> {
+        Ok(Some(procfs::get_command_line(self.get_pid(window)?)?))
+    }
+
+    fn get_process_age(&self, window: WindowId) -> Result<Option<Duration>> {
+        Ok(Some(procfs::get_process_age(self.get_pid(window)?)?))
+    }
+
+    fn get_title(&self, window: WindowId) -> Result<String> {
+        Ok(self.get_container(window)?.title)
+    }
+
+    fn get_class(&self, window: WindowId) -> Result<Option<String>> {
+        Ok(self.get_container(window)?.class)
+    }
+
+    fn get_workspace(&self, window: WindowId) -> Result<Option<WindowWorkspace>> {
+        let container = self.get_container(window)?;
+        Ok(Some(WindowWorkspace {
+            name: container.workspace,
+            floating: container.floating,
+        }))
+    }
+
+    fn move_to_workspace(&self, window: WindowId, workspace: &WindowWorkspace) -> Result<()> {
+        if workspace.name == SCRATCHPAD_WORKSPACE {
+            return self.run_command(window, "move scratchpad");
+        }
+
+        self.run_command(
+            window,
+            &format!(
+                "move container to workspace {}, floating {}",
+                quote(&workspace.name),
+                if workspace.floating {
+                    "enable"
+                } else {
+                    "disable"
+                }
+            ),
+        )
+    }
+
+    fn get_monitors(&self) -> Result<Vec<MonitorData>> {
+        let outputs = self.request(GET_OUTPUTS, "")?;
+        let workspaces = self.request(GET_WORKSPACES, "")?;
+
+        let mut monitors = Vec::new();
+        for output in outputs.as_array().into_iter().flatten() {
+            if output["active"] != Value::Bool(true) {
+                continue;
+            }
+            let name = output["name"].as_str().unwrap_or_default();
+
+            // Workspace rects leave out bars, so a visible workspace on the output gives its work area
+            let bounds = get_screen_rect(&output["rect"]);
+            let work_area = workspaces
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|workspace| workspace["output"] == name && workspace["visible"] == true)
+                .map(|workspace| get_screen_rect(&workspace["rect"]))
+                .unwrap_or(bounds);
+
+            monitors.push(MonitorData {
+                id: name.to_string(),
+                bounds,
+                work_area,
+                scale: output["scale"].as_f64().unwrap_or(1.0),
+                primary: output["primary"] == true,
+            });
+        }
+
+        Ok(monitors)
+    }
+
+    fn get_rect(&self, window: WindowId) -> Result<(WindowPosition, WindowSize)> {
+        Ok(self.get_container(window)?.rect)
+    }
+
+    fn is_minimized(&self, window: WindowId) -> Result<bool> {
+        Ok(self.get_container(window)?.workspace == SCRATCHPAD_WORKSPACE)
+    }
+
+    fn is_maximized(&self, window: WindowId) -> Result<bool> {
+        Ok(self.get_container(window)?.fullscreen)
+    }
+
+    fn can_reposition(&self, window: WindowId) -> Result<bool> {
+        Ok(self.get_container(window)?.floating)
+    }
+
+    fn reposition_and_resize(
+        &self,
+        window: WindowId,
+        position: &WindowPosition,
+        size: &WindowSize,
+    ) -> Result<()> {
+        // Tiled windows are sized by the window manager, so only floating windows can be placed
+        if !self.get_container(window)?.floating {
+            return Ok(());
+        }
+
+        self.run_command(
+            window,
+            &format!(
+                "move position {} px {} px, resize set {} px {} px",
+                position.left, position.top, size.width, size.height
+            ),
+        )
+    }
+
+    fn restore(&self, window: WindowId) -> Result<()> {
+        let container = self.get_container(window)?;
+        if container.workspace == SCRATCHPAD_WORKSPACE {
+            self.run_command(window, "scratchpad show")
+        } else if container.fullscreen {
+            self.run_command(window, "fullscreen disable")
+        } else {
+            Ok(())
+        }
+    }
+
+    fn minimize(&self, window: WindowId) -> Result<()> {
+        self.run_command(window, "move scratchpad")
+    }
+
+    fn maximize(&self, window: WindowId) -> Result<()> {
+        self.run_command(window, "fullscreen enable")
+    }
+
+    fn close(&self, window: WindowId) -> Result<()> {
+        self.run_command(window, "kill")
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.forget_containers();
+        thread::sleep(duration);
+    }
+
+    fn wait_for_window_event(&self, timeout: Duration) {
+        self.forget_containers();
+        let start = Instant::now();
+        if let Err(error) = self.read_window_event(timeout) {
+            if verbose() {
+                printwarning!("failed to wait for window event: {:#}", error);
+            }
+            thread::sleep(timeout.saturating_sub(start.elapsed()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::{
+        fs,
+        os::unix::net::UnixListener,
+        process,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    const EDITOR: u64 = 10;
+    const TERMINAL: u64 = 12;
+    const NOTES: u64 = 20;
+
+    /// What the stub window manager has been asked to do
+    #[derive(Default)]
+    struct StubState {
+        tree_requests: usize,
+        commands: Vec<String>,
+        editor_fullscreen: bool,
+    }
+
+    /// An IPC server that answers like i3 would, from a fixed layout with an editor (tiled), a terminal (floating) and notes (in the scratchpad)
+    struct StubServer {
+        socket_path: PathBuf,
+        state: Arc<Mutex<StubState>>,
+    }
+
+    impl StubServer {
+        fn start() -> Self {
+            static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+            let socket_path = env::temp_dir().join(format!(
+                "windough-i3-test-{}-{}.sock",
+                process::id(),
+                NEXT_ID.fetch_add(1, Ordering::Relaxed)
+            ));
+            let _ = fs::remove_file(&socket_path);
+            let listener = UnixListener::bind(&socket_path).unwrap();
+            let state = Arc::new(Mutex::new(StubState {
+                editor_fullscreen: true,
+                ..StubState::default()
+            }));
+
+            let server_state = state.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let state = server_state.clone();
+                    thread::spawn(move || serve(stream.unwrap(), &state));
+                }
+            });
+
+            StubServer { socket_path, state }
+        }
+
+        fn tree_requests(&self) -> usize {
+            self.state.lock().unwrap().tree_requests
+        }
+
+        fn commands(&self) -> Vec<String> {
+            self.state.lock().unwrap().commands.clone()
+        }
+    }
+
+    impl Drop for StubServer {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.socket_path);
+        }
+    }
+
+    fn serve(mut stream: UnixStream, state: &Mutex<StubState>) {
+        while let Ok((message_type, body)) = read_message(&mut stream) {
+            let reply = {
+                let mut state = state.lock().unwrap();
+                match message_type {
+                    RUN_COMMAND => {
+                        let command = String::from_utf8(body).unwrap();
+                        if command == format!("[con_id={}] fullscreen disable", EDITOR) {
+                            state.editor_fullscreen = false;
+                        }
+                        state.commands.push(command);
+                        json!([{ "success": true }])
+                    }
+                    GET_TREE => {
+                        state.tree_requests += 1;
+                        get_tree(state.editor_fullscreen)
+                    }
+                    GET_OUTPUTS => json!([
+                        { "name": "HDMI-1", "active": true, "primary": true, "rect": rect(0, 0, 1920, 1080) },
+                        { "name": "DP-1", "active": false, "rect": rect(0, 0, 0, 0) },
+                    ]),
+                    GET_WORKSPACES => json!([
+                        { "name": "1", "output": "HDMI-1", "visible": true, "rect": rect(0, 30, 1920, 1050) },
+                    ]),
+                    SUBSCRIBE => json!({ "success": true }),
+                    _ => json!({ "success": false }),
+                }
+            };
+            let body = reply.to_string();
+            let mut message = Vec::new();
+            message.extend_from_slice(MAGIC);
+            message.extend_from_slice(&(body.len() as u32).to_ne_bytes());
+            message.extend_from_slice(&message_type.to_ne_bytes());
+            message.extend_from_slice(body.as_bytes());
+            if stream.write_all(&message).is_err() {
+                return;
+            }
+        }
+    }
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Value {
+        json!({ "x": x, "y": y, "width": width, "height": height })
+    }
+
+    fn get_tree(editor_fullscreen: bool) -> Value {
+        let pid = process::id();
+        json!({
+            "id": 1,
+            "type": "root",
+            "nodes": [{
+                "id": 2,
+                "type": "output",
+                "name": "HDMI-1",
+                "nodes": [{
+                    "id": 3,
+                    "type": "workspace",
+                    "name": "1",
+                    "nodes": [{
+                        "id": EDITOR,
+                        "type": "con",
+                        "name": "Editor",
+                        "app_id": "editor",
+                        "pid": pid,
+                        "fullscreen_mode": if editor_fullscreen { 1 } else { 0 },
+                        "rect": rect(0, 30, 1920, 1050),
+                    }],
+                    "floating_nodes": [{
+                        "id": 11,
+                        "type": "floating_con",
+                        "rect": rect(100, 50, 800, 600),
+                        "nodes": [{
+                            "id": TERMINAL,
+                            "type": "con",
+                            "name": "Terminal",
+                            "window": 4000,
+                            "window_properties": { "class": "Terminal" },
+                            "pid": pid,
+                            "rect": rect(0, 0, 800, 580),
+                        }],
+                    }],
+                }],
+            }, {
+                "id": 4,
+                "type": "output",
+                "name": "__i3",
+                "nodes": [{
+                    "id": 5,
+                    "type": "workspace",
+                    "name": SCRATCHPAD_WORKSPACE,
+                    "floating_nodes": [{
+                        "id": NOTES,
+                        "type": "con",
+                        "name": "Notes",
+                        "pid": pid,
+                        "rect": rect(0, 0, 400, 300),
+                    }],
+                }],
+            }],
+        })
+    }
+
+    fn connect(server: &StubServer) -> I3WindowSystem {
+        let mut window_system = I3WindowSystem::connect(&server.socket_path).unwrap();
+        // Process IDs come from the tree, so no X server is needed
+        window_system.x11 = None;
+        window_system
+    }
+
+    #[test]
+    fn reads_window_details_from_tree() {
+        let server = StubServer::start();
+        let window_system = connect(&server);
+
+        assert_eq!(
+            window_system.get_open_windows().unwrap(),
+            [WindowId(EDITOR), WindowId(TERMINAL), WindowId(NOTES)]
+        );
+        assert_eq!(window_system.get_title(WindowId(EDITOR)).unwrap(), "Editor");
+        assert_eq!(
+            window_system
+                .get_class(WindowId(TERMINAL))
+                .unwrap()
+                .as_deref(),
+            Some("Terminal")
+        );
+        assert_eq!(
+            window_system.get_module_path(WindowId(EDITOR)).unwrap(),
+            env::current_exe().unwrap().to_string_lossy()
+        );
+        assert!(window_system.is_maximized(WindowId(EDITOR)).unwrap());
+        assert!(window_system.is_minimized(WindowId(NOTES)).unwrap());
+        assert!(window_system.can_reposition(WindowId(TERMINAL)).unwrap());
+        assert!(!window_system.can_reposition(WindowId(EDITOR)).unwrap());
+        // Floating windows are placed by their floating container
+        assert_eq!(
+            window_system.get_rect(WindowId(TERMINAL)).unwrap(),
+            (
+                WindowPosition { top: 50, left: 100 },
+                WindowSize {
+                    width: 800,
+                    height: 600
+                }
+            )
+        );
+        assert_eq!(
+            window_system.get_workspace(WindowId(TERMINAL)).unwrap(),
+            Some(WindowWorkspace {
+                name: String::from("1"),
+                floating: true
+            })
+        );
+        assert!(window_system.get_title(WindowId(99)).is_err());
+
+        // Every lookup used the tree fetched to list the open windows
+        assert_eq!(server.tree_requests(), 1);
+    }
+
+    #[test]
+    fn fetches_tree_again_after_command() {
+        let server = StubServer::start();
+        let window_system = connect(&server);
+
+        window_system.get_open_windows().unwrap();
+        window_system.restore(WindowId(EDITOR)).unwrap();
+        assert_eq!(
+            server.commands(),
+            [format!("[con_id={}] fullscreen disable", EDITOR)]
+        );
+        assert!(!window_system.is_maximized(WindowId(EDITOR)).unwrap());
+        assert_eq!(server.tree_requests(), 2);
+
+        // Time passing may change the layout too
+        window_system.sleep(Duration::ZERO);
+        window_system.is_maximized(WindowId(EDITOR)).unwrap();
+        assert_eq!(server.tree_requests(), 3);
+    }
+
+    #[test]
+    fn sends_layout_commands() {
+        let server = StubServer::start();
+        let window_system = connect(&server);
+        let position = WindowPosition { top: 20, left: 40 };
+        let size = WindowSize {
+            width: 640,
+            height: 480,
+        };
+
+        window_system
+            .reposition_and_resize(WindowId(TERMINAL), &position, &size)
+            .unwrap();
+        // Tiled windows are left to the window manager
+        window_system
+            .reposition_and_resize(WindowId(EDITOR), &position, &size)
+            .unwrap();
+        window_system.restore(WindowId(NOTES)).unwrap();
+        window_system
+            .move_to_workspace(
+                WindowId(EDITOR),
+                &WindowWorkspace {
+                    name: String::from("2: \"web\""),
+                    floating: false,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            server.commands(),
+            [
+                format!(
+                    "[con_id={}] move position 40 px 20 px, resize set 640 px 480 px",
+                    TERMINAL
+                ),
+                format!("[con_id={}] scratchpad show", NOTES),
+                format!(
+                    "[con_id={}] move container to workspace \"2: \\\"web\\\"\", floating disable",
+                    EDITOR
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_monitors_from_outputs_and_workspaces() {
+        let server = StubServer::start();
+        let window_system = connect(&server);
+
+        assert_eq!(
+            window_system.get_monitors().unwrap(),
+            [MonitorData {
+                id: String::from("HDMI-1"),
+                bounds: ScreenRect {
+                    left: 0,
+                    top: 0,
+                    width: 1920,
+                    height: 1080
+                },
+                work_area: ScreenRect {
+                    left: 0,
+                    top: 30,
+                    width: 1920,
+                    height: 1050
+                },
+                scale: 1.0,
+                primary: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn waits_for_window_events() {
+        let server = StubServer::start();
+        let window_system = connect(&server);
+
+        // The stub accepts the subscription but never sends an event
+        let start = Instant::now();
+        assert!(!window_system
+            .read_window_event(Duration::from_millis(50))
+            .unwrap());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod fake;
+mod get_window_system;
+#[cfg(target_os = "linux")]
+mod i3;
+#[cfg(target_os = "linux")]
+mod procfs;
+mod window_system;
+#[cfg(windows)]
+mod windows;
+#[cfg(target_os = "linux")]
+mod x11;
+
+#[cfg(test)]
+pub use fake::{FakeWindow, FakeWindowSystem};
+pub use get_window_system::get_window_system;
+#[cfg(target_os = "linux")]
+pub use i3::I3WindowSystem;
+pub use window_system::{CommandLine, WindowId, WindowSystem};
+#[cfg(windows)]
+pub use windows::WindowsWindowSystem;
+#[cfg(target_os = "linux")]
+pub use x11::X11WindowSystem;
@@ -0,0 +1,102 @@
+use super::CommandLine;
+use anyhow::{Context, Result};
+use std::{fs, time::Duration};
+
+// The units of process start times in `/proc/<pid>/stat` (USER_HZ, which is 100 on every architecture Linux supports)
+const CLOCK_TICKS_PER_SECOND: f64 = 100.0;
+
+/// Gets the path to the executable of a process from `/proc/<pid>/exe`
+///
+/// # Errors
+///
+/// Returns an error if the link could not be read (for example, if the process has exited or belongs to another user)
+pub fn get_module_path(pid: u32) -> Result<String> {
+    let exe_path = fs::read_link(format!("/proc/{}/exe", pid))
+        .with_context(|| format!("Failed to resolve executable for process {}", pid))?;
+    Ok(exe_path.to_string_lossy().to_string())
+}
+
+/// Gets the arguments and working directory of a process from `/proc/<pid>/cmdline` and `/proc/<pid>/cwd`
+///
+/// # Errors
+///
+/// Returns an error if the command line could not be read; an unreadable working directory is left as `None`
+pub fn get_command_line(pid: u32) -> Result<CommandLine> {
+    let raw_command_line = fs::read(format!("/proc/{}/cmdline", pid))
+        .with_context(|| format!("Failed to read command line of process {}", pid))?;
+    let args = parse_arguments(&raw_command_line);
+
+    let working_directory = fs::read_link(format!("/proc/{}/cwd", pid))
+        .ok()
+        .map(|path| path.to_string_lossy().to_string());
+
+    Ok(CommandLine {
+        args,
+        working_directory,
+    })
+}
+
+/// Splits the contents of `/proc/<pid>/cmdline` into the arguments after the executable
+///
+/// Each argument is followed by a NUL, so only the empty piece after the last one is dropped (arguments that are themselves empty are kept)
+fn parse_arguments(raw_command_line: &[u8]) -> Vec<String> {
+    let raw_command_line = raw_command_line
+        .strip_suffix(&[0])
+        .unwrap_or(raw_command_line);
+    if raw_command_line.is_empty() {
+        return Vec::new();
+    }
+
+    raw_command_line
+        .split(|byte| *byte == 0)
+        .skip(1) // The executable itself
+        .map(|arg| String::from_utf8_lossy(arg).to_string())
+        .collect()
+}
+
+/// Gets how long ago a process was started, from `/proc/<pid>/stat` and `/proc/uptime`
+///
+/// # Errors
+///
+/// Returns an error if either file could not be read or parsed
+pub fn get_process_age(pid: u32) -> Result<Duration> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid))
+        .with_context(|| format!("Failed to read status of process {}", pid))?;
+    // The process name (field 2) is in brackets and may contain spaces, so fields are counted from after it
+    let start_ticks: f64 = stat
+        .rsplit_once(')')
+        .and_then(|(_, fields)| fields.split_whitespace().nth(19))
+        .and_then(|field| field.parse().ok())
+        .with_context(|| format!("Failed to parse start time of process {}", pid))?;
+
+    let uptime: f64 = fs::read_to_string("/proc/uptime")
+        .with_context(|| "Failed to read system uptime")?
+        .split_whitespace()
+        .next()
+        .and_then(|field| field.parse().ok())
+        .with_context(|| "Failed to parse system uptime")?;
+
+    Ok(Duration::from_secs_f64(
+        (uptime - start_ticks / CLOCK_TICKS_PER_SECOND).max(0.0),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_empty_arguments() {
+        assert_eq!(
+            parse_arguments(b"/usr/bin/app\0--name\0\0--flag\0\0"),
+            ["--name", "", "--flag", ""]
+        );
+    }
+
+    #[test]
+    fn parses_command_line_without_arguments() {
+        assert!(parse_arguments(b"/usr/bin/app\0").is_empty());
+        // Kernel threads have an empty command line
+        assert!(parse_arguments(b"").is_empty());
+    }
+}
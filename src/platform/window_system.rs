@@ -0,0 +1,142 @@
+use crate::{
+    data::{MonitorData, WindowPosition, WindowSize, WindowWorkspace},
+    utils::launch_application,
+};
+use anyhow::Result;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How often the open windows are polled by the default `WindowSystem::wait_for_window_event`
+const WINDOW_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// An opaque identifier for a top-level window, only meaningful to the window system that produced it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WindowId(pub u64);
+
+/// How a process was started
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommandLine {
+    /// The arguments passed to the process, not including the executable itself
+    pub args: Vec<String>,
+    pub working_directory: Option<String>,
+}
+
+/// The operations Windough needs from the underlying window system
+///
+/// Commands take a `&dyn WindowSystem` rather than calling platform APIs directly, so that the same save/load logic can run against any backend
+pub trait WindowSystem {
+    /// Gets all currently open windows (that meet the backend's criteria for a user-facing window)
+    fn get_open_windows(&self) -> Result<Vec<WindowId>>;
+
+    /// Gets the path to the executable of the process that owns a window
+    fn get_module_path(&self, window: WindowId) -> Result<String>;
+
+    /// Gets the title of a window
+    fn get_title(&self, window: WindowId) -> Result<String>;
+
+    /// Gets the arguments and working directory of the process that owns a window, if the backend can read them
+    fn get_command_line(&self, _window: WindowId) -> Result<Option<CommandLine>> {
+        Ok(None)
+    }
+
+    /// Gets how long ago the process that owns a window was started, if the backend can tell
+    fn get_process_age(&self, _window: WindowId) -> Result<Option<Duration>> {
+        Ok(None)
+    }
+
+    /// Gets the class of a window (the Wayland app id or X11 `WM_CLASS`), if the backend knows it
+    fn get_class(&self, _window: WindowId) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Gets the workspace a window is on, for backends where a tiling window manager owns the layout
+    fn get_workspace(&self, _window: WindowId) -> Result<Option<WindowWorkspace>> {
+        Ok(None)
+    }
+
+    /// Moves a window to a workspace, for backends where a tiling window manager owns the layout
+    fn move_to_workspace(&self, _window: WindowId, _workspace: &WindowWorkspace) -> Result<()> {
+        Ok(())
+    }
+
+    /// Gets the monitors that are currently connected, if the backend knows them
+    fn get_monitors(&self) -> Result<Vec<MonitorData>> {
+        Ok(Vec::new())
+    }
+
+    /// Gets the current position and size of a window
+    fn get_rect(&self, window: WindowId) -> Result<(WindowPosition, WindowSize)>;
+
+    /// Checks whether a window is minimized
+    fn is_minimized(&self, window: WindowId) -> Result<bool>;
+
+    /// Checks whether a window is maximized
+    fn is_maximized(&self, window: WindowId) -> Result<bool>;
+
+    /// Checks whether `reposition_and_resize` can move a window (for example, tiled windows are placed by the window manager instead)
+    fn can_reposition(&self, _window: WindowId) -> Result<bool> {
+        Ok(true)
+    }
+
+    /// Moves and resizes a window
+    fn reposition_and_resize(
+        &self,
+        window: WindowId,
+        position: &WindowPosition,
+        size: &WindowSize,
+    ) -> Result<()>;
+
+    /// Restores a window by one step: a minimized window is shown again (maximized, if it was before), and any other window is un-maximized
+    fn restore(&self, window: WindowId) -> Result<()>;
+
+    /// Minimizes a window
+    fn minimize(&self, window: WindowId) -> Result<()>;
+
+    /// Maximizes a window
+    fn maximize(&self, window: WindowId) -> Result<()>;
+
+    /// Asks a window to close
+    fn close(&self, window: WindowId) -> Result<()>;
+
+    /// Launches an application with arguments, optionally in a working directory
+    fn launch_application(
+        &self,
+        application_path: &str,
+        application_args: &[String],
+        working_directory: Option<&str>,
+    ) -> Result<()> {
+        launch_application(application_path, application_args, working_directory)
+    }
+
+    /// Waits for a duration before the next look at the open windows
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+
+    /// Gets the current time, as seen by `sleep`
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    /// Waits until a window may have been opened or closed, or until `timeout` has passed, whichever is first
+    ///
+    /// Backends that can be notified of window changes should override this; by default the open windows are polled at a short interval
+    fn wait_for_window_event(&self, timeout: Duration) {
+        let deadline = self.now() + timeout;
+        let initial_windows = self.get_open_windows().ok();
+
+        loop {
+            let now = self.now();
+            if now >= deadline {
+                return;
+            }
+            self.sleep((deadline - now).min(WINDOW_POLL_INTERVAL));
+
+            if initial_windows.is_some() && self.get_open_windows().ok() != initial_windows {
+                return;
+            }
+        }
+    }
+}
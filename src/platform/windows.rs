@@ -0,0 +1,553 @@
+use super::{CommandLine, WindowId, WindowSystem};
+use crate::data::{MonitorData, ScreenRect, WindowPosition, WindowSize};
+use anyhow::{bail, Context, Result};
+use std::{
+    ffi::OsString,
+    mem::{size_of, zeroed},
+    os::windows::ffi::{OsStrExt, OsStringExt},
+    ptr::{null, null_mut},
+    slice,
+    time::Duration,
+};
+use winapi::{
+    shared::{
+        minwindef::{
+            BOOL, DWORD, FALSE, FILETIME, HLOCAL, LPARAM, LPCVOID, LPVOID, MAX_PATH, TRUE, ULONG,
+        },
+        ntdef::{NTSTATUS, PVOID, UNICODE_STRING},
+        windef::{HDC, HMONITOR, HWND, LPRECT, RECT},
+        winerror::S_OK,
+    },
+    um::{
+        errhandlingapi::GetLastError,
+        handleapi::CloseHandle,
+        memoryapi::ReadProcessMemory,
+        processthreadsapi::{GetProcessTimes, OpenProcess},
+        psapi::GetModuleFileNameExW,
+        shellapi::CommandLineToArgvW,
+        shellscalingapi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI},
+        sysinfoapi::GetSystemTimeAsFileTime,
+        winbase::LocalFree,
+        winnt::{HANDLE, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ, WCHAR},
+        winuser::{
+            EnumDisplayMonitors, EnumWindows, GetClassNameW, GetMonitorInfoW, GetWindowRect,
+            GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId, IsIconic,
+            IsWindowVisible, IsZoomed, PostMessageW, SetWindowPos, ShowWindow, HWND_TOP,
+            LPMONITORINFO, MONITORINFOEXW, MONITORINFOF_PRIMARY, SW_MAXIMIZE, SW_MINIMIZE,
+            SW_RESTORE, WM_CLOSE,
+        },
+    },
+};
+
+/// The Win32 window system
+pub struct WindowsWindowSystem;
+
+fn to_hwnd(window: WindowId) -> HWND {
+    window.0 as usize as HWND
+}
+
+impl WindowSystem for WindowsWindowSystem {
+    fn get_open_windows(&self) -> Result<Vec<WindowId>> {
+        let mut hwnds: Vec<HWND> = Vec::new();
+
+        unsafe {
+            if EnumWindows(
+                Some(enum_windows_callback),
+                &mut hwnds as *mut Vec<HWND> as LPARAM,
+            ) == 0
+            {
+                bail!(
+                    "Failed to enumerate windows (Win32 error: {})",
+                    GetLastError()
+                );
+            }
+        }
+
+        Ok(hwnds
+            .into_iter()
+            .map(|hwnd| WindowId(hwnd as usize as u64))
+            .collect())
+    }
+
+    fn get_module_path(&self, window: WindowId) -> Result<String> {
+        let (process_id, process_handle) = open_window_process(window)?;
+
+        unsafe {
+            let mut raw_module_path: [WCHAR; MAX_PATH] = zeroed();
+            let path_length = GetModuleFileNameExW(
+                process_handle,
+                null_mut(),
+                raw_module_path.as_mut_ptr(),
+                raw_module_path.len() as DWORD,
+            );
+            if path_length == 0 {
+                CloseHandle(process_handle);
+                bail!(
+                    "Failed to get module path for process {} (Win32 error: {})",
+                    process_id,
+                    GetLastError()
+                );
+            }
+
+            CloseHandle(process_handle);
+
+            let module_path = OsString::from_wide(&raw_module_path)
+                .to_string_lossy()
+                .trim_matches(char::from(0))
+                .to_string();
+            Ok(module_path)
+        }
+    }
+
+    fn get_title(&self, window: WindowId) -> Result<String> {
+        unsafe {
+            let length = GetWindowTextLengthW(to_hwnd(window));
+            let mut raw_title: Vec<WCHAR> = vec![0; length as usize + 1];
+            let copied = GetWindowTextW(
+                to_hwnd(window),
+                raw_title.as_mut_ptr(),
+                raw_title.len() as i32,
+            );
+            Ok(OsString::from_wide(&raw_title[..copied as usize])
+                .to_string_lossy()
+                .to_string())
+        }
+    }
+
+    fn get_class(&self, window: WindowId) -> Result<Option<String>> {
+        unsafe {
+            // Window class names are limited to 256 characters
+            let mut raw_class: [WCHAR; 257] = zeroed();
+            let length = GetClassNameW(
+                to_hwnd(window),
+                raw_class.as_mut_ptr(),
+                raw_class.len() as i32,
+            );
+            if length == 0 {
+                bail!(
+                    "Failed to get window class name (Win32 error: {})",
+                    GetLastError()
+                );
+            }
+            Ok(Some(
+                OsString::from_wide(&raw_class[..length as usize])
+                    .to_string_lossy()
+                    .to_string(),
+            ))
+        }
+    }
+
+    fn get_process_age(&self, window: WindowId) -> Result<Option<Duration>> {
+        let (process_id, process_handle) = open_window_process(window)?;
+        let mut creation_time: FILETIME = unsafe { zeroed() };
+        let mut exit_time: FILETIME = unsafe { zeroed() };
+        let mut kernel_time: FILETIME = unsafe { zeroed() };
+        let mut user_time: FILETIME = unsafe { zeroed() };
+        let result = unsafe {
+            GetProcessTimes(
+                process_handle,
+                &mut creation_time,
+                &mut exit_time,
+                &mut kernel_time,
+                &mut user_time,
+            )
+        };
+        let error = unsafe { GetLastError() };
+        unsafe { CloseHandle(process_handle) };
+        if result == 0 {
+            bail!(
+                "Failed to get start time of process {} (Win32 error: {})",
+                process_id,
+                error
+            );
+        }
+
+        let mut now: FILETIME = unsafe { zeroed() };
+        unsafe { GetSystemTimeAsFileTime(&mut now) };
+
+        // FILETIMEs count 100-nanosecond intervals
+        let age = to_u64(&now).saturating_sub(to_u64(&creation_time));
+        Ok(Some(Duration::from_nanos(age.saturating_mul(100))))
+    }
+
+    fn get_command_line(&self, window: WindowId) -> Result<Option<CommandLine>> {
+        let (process_id, process_handle) = open_window_process(window)?;
+        let command_line_result = unsafe { read_process_parameters(process_handle) };
+        unsafe { CloseHandle(process_handle) };
+
+        let (raw_command_line, working_directory) = command_line_result
+            .with_context(|| format!("Failed to read parameters of process {}", process_id))?;
+
+        let mut raw_command_line: Vec<u16> = raw_command_line.encode_wide().collect();
+        raw_command_line.push(0);
+        let mut arg_count = 0;
+        let mut args = Vec::new();
+        unsafe {
+            let raw_args = CommandLineToArgvW(raw_command_line.as_ptr(), &mut arg_count);
+            if raw_args.is_null() {
+                bail!(
+                    "Failed to split command line of process {} (Win32 error: {})",
+                    process_id,
+                    GetLastError()
+                );
+            }
+
+            // The first argument is the executable itself
+            for i in 1..(arg_count as isize) {
+                let raw_arg = *raw_args.offset(i);
+                let length = (0..).take_while(|&j| *raw_arg.offset(j) != 0).count();
+                args.push(
+                    OsString::from_wide(slice::from_raw_parts(raw_arg, length))
+                        .to_string_lossy()
+                        .to_string(),
+                );
+            }
+
+            LocalFree(raw_args as HLOCAL);
+        }
+
+        Ok(Some(CommandLine {
+            args,
+            working_directory: Some(
+                working_directory
+                    .to_string_lossy()
+                    .trim_end_matches('\\')
+                    .to_string(),
+            ),
+        }))
+    }
+
+    fn get_monitors(&self) -> Result<Vec<MonitorData>> {
+        let mut hmonitors: Vec<HMONITOR> = Vec::new();
+        unsafe {
+            if EnumDisplayMonitors(
+                null_mut(),
+                null(),
+                Some(enum_monitors_callback),
+                &mut hmonitors as *mut Vec<HMONITOR> as LPARAM,
+            ) == 0
+            {
+                bail!(
+                    "Failed to enumerate monitors (Win32 error: {})",
+                    GetLastError()
+                );
+            }
+        }
+
+        let mut monitors = Vec::new();
+        for hmonitor in hmonitors {
+            unsafe {
+                let mut monitor_info: MONITORINFOEXW = zeroed();
+                monitor_info.cbSize = size_of::<MONITORINFOEXW>() as DWORD;
+                if GetMonitorInfoW(
+                    hmonitor,
+                    &mut monitor_info as *mut MONITORINFOEXW as LPMONITORINFO,
+                ) == 0
+                {
+                    bail!(
+                        "Failed to get monitor info (Win32 error: {})",
+                        GetLastError()
+                    );
+                }
+
+                let mut dpi_x = 0;
+                let mut dpi_y = 0;
+                let scale = if GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y)
+                    == S_OK
+                {
+                    dpi_x as f64 / 96.0
+                } else {
+                    1.0
+                };
+
+                monitors.push(MonitorData {
+                    id: OsString::from_wide(&monitor_info.szDevice)
+                        .to_string_lossy()
+                        .trim_matches(char::from(0))
+                        .to_string(),
+                    bounds: to_screen_rect(&monitor_info.rcMonitor),
+                    work_area: to_screen_rect(&monitor_info.rcWork),
+                    scale,
+                    primary: monitor_info.dwFlags & MONITORINFOF_PRIMARY != 0,
+                });
+            }
+        }
+
+        Ok(monitors)
+    }
+
+    fn get_rect(&self, window: WindowId) -> Result<(WindowPosition, WindowSize)> {
+        let mut window_rect = RECT {
+            top: 0,
+            bottom: 0,
+            left: 0,
+            right: 0,
+        };
+        unsafe {
+            if GetWindowRect(to_hwnd(window), &mut window_rect) == 0 {
+                bail!(
+                    "Failed to get window rect (Win32 error: {})",
+                    GetLastError()
+                );
+            }
+        }
+
+        Ok((
+            WindowPosition {
+                top: window_rect.top,
+                left: window_rect.left,
+            },
+            WindowSize {
+                width: window_rect.right - window_rect.left,
+                height: window_rect.bottom - window_rect.top,
+            },
+        ))
+    }
+
+    fn is_minimized(&self, window: WindowId) -> Result<bool> {
+        Ok(unsafe { IsIconic(to_hwnd(window)) != 0 })
+    }
+
+    fn is_maximized(&self, window: WindowId) -> Result<bool> {
+        Ok(unsafe { IsZoomed(to_hwnd(window)) != 0 })
+    }
+
+    fn reposition_and_resize(
+        &self,
+        window: WindowId,
+        position: &WindowPosition,
+        size: &WindowSize,
+    ) -> Result<()> {
+        unsafe {
+            if SetWindowPos(
+                to_hwnd(window),
+                HWND_TOP,
+                position.left,
+                position.top,
+                size.width,
+                size.height,
+                0,
+            ) == 0
+            {
+                bail!(
+                    "Failed to reposition/resize window (Win32 error: {})",
+                    GetLastError()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn restore(&self, window: WindowId) -> Result<()> {
+        unsafe { ShowWindow(to_hwnd(window), SW_RESTORE) };
+        Ok(())
+    }
+
+    fn minimize(&self, window: WindowId) -> Result<()> {
+        unsafe { ShowWindow(to_hwnd(window), SW_MINIMIZE) };
+        Ok(())
+    }
+
+    fn maximize(&self, window: WindowId) -> Result<()> {
+        unsafe { ShowWindow(to_hwnd(window), SW_MAXIMIZE) };
+        Ok(())
+    }
+
+    fn close(&self, window: WindowId) -> Result<()> {
+        unsafe { PostMessageW(to_hwnd(window), WM_CLOSE, 0, 0) };
+        Ok(())
+    }
+}
+
+/// Opens the process that owns a window for reading
+///
+/// The returned handle must be closed with `CloseHandle`
+fn open_window_process(window: WindowId) -> Result<(DWORD, HANDLE)> {
+    unsafe {
+        let mut process_id: DWORD = 0;
+        if GetWindowThreadProcessId(to_hwnd(window), &mut process_id) == 0 {
+            bail!(
+                "Failed to get window process ID (Win32 error: {})",
+                GetLastError()
+            );
+        }
+
+        let process_handle = OpenProcess(
+            PROCESS_QUERY_INFORMATION | PROCESS_VM_READ,
+            FALSE,
+            process_id,
+        );
+        if process_handle.is_null() {
+            bail!(
+                "Failed to get handle for process {} (Win32 error: {})",
+                process_id,
+                GetLastError()
+            );
+        }
+
+        Ok((process_id, process_handle))
+    }
+}
+
+#[link(name = "ntdll")]
+extern "system" {
+    fn NtQueryInformationProcess(
+        process_handle: HANDLE,
+        process_information_class: u32,
+        process_information: PVOID,
+        process_information_length: ULONG,
+        return_length: *mut ULONG,
+    ) -> NTSTATUS;
+}
+
+const PROCESS_BASIC_INFORMATION_CLASS: u32 = 0;
+
+#[repr(C)]
+struct ProcessBasicInformation {
+    exit_status: NTSTATUS,
+    peb_base_address: PVOID,
+    affinity_mask: usize,
+    base_priority: i32,
+    unique_process_id: usize,
+    inherited_from_unique_process_id: usize,
+}
+
+// Offsets into the (undocumented) PEB and RTL_USER_PROCESS_PARAMETERS structures
+#[cfg(target_pointer_width = "64")]
+const PEB_PROCESS_PARAMETERS_OFFSET: usize = 0x20;
+#[cfg(target_pointer_width = "64")]
+const PARAMETERS_CURRENT_DIRECTORY_OFFSET: usize = 0x38;
+#[cfg(target_pointer_width = "64")]
+const PARAMETERS_COMMAND_LINE_OFFSET: usize = 0x70;
+#[cfg(target_pointer_width = "32")]
+const PEB_PROCESS_PARAMETERS_OFFSET: usize = 0x10;
+#[cfg(target_pointer_width = "32")]
+const PARAMETERS_CURRENT_DIRECTORY_OFFSET: usize = 0x24;
+#[cfg(target_pointer_width = "32")]
+const PARAMETERS_COMMAND_LINE_OFFSET: usize = 0x40;
+
+/// Reads the command line and current directory of another process from its process environment block
+///
+/// # Safety
+///
+/// `process_handle` must be a valid handle opened with `PROCESS_QUERY_INFORMATION | PROCESS_VM_READ`
+unsafe fn read_process_parameters(process_handle: HANDLE) -> Result<(OsString, OsString)> {
+    let mut basic_information: ProcessBasicInformation = zeroed();
+    let status = NtQueryInformationProcess(
+        process_handle,
+        PROCESS_BASIC_INFORMATION_CLASS,
+        &mut basic_information as *mut ProcessBasicInformation as PVOID,
+        size_of::<ProcessBasicInformation>() as ULONG,
+        null_mut(),
+    );
+    if status < 0 {
+        bail!(
+            "Failed to query process information (NTSTATUS: {:#x})",
+            status
+        );
+    }
+
+    let parameters: usize = read_process_value(
+        process_handle,
+        basic_information.peb_base_address as usize + PEB_PROCESS_PARAMETERS_OFFSET,
+    )?;
+    let command_line: UNICODE_STRING =
+        read_process_value(process_handle, parameters + PARAMETERS_COMMAND_LINE_OFFSET)?;
+    let current_directory: UNICODE_STRING = read_process_value(
+        process_handle,
+        parameters + PARAMETERS_CURRENT_DIRECTORY_OFFSET,
+    )?;
+
+    Ok((
+        read_process_string(process_handle, &command_line)?,
+        read_process_string(process_handle, &current_directory)?,
+    ))
+}
+
+unsafe fn read_process_value<T>(process_handle: HANDLE, address: usize) -> Result<T> {
+    let mut value: T = zeroed();
+    if ReadProcessMemory(
+        process_handle,
+        address as LPCVOID,
+        &mut value as *mut T as LPVOID,
+        size_of::<T>(),
+        null_mut(),
+    ) == 0
+    {
+        bail!(
+            "Failed to read process memory (Win32 error: {})",
+            GetLastError()
+        );
+    }
+    Ok(value)
+}
+
+unsafe fn read_process_string(process_handle: HANDLE, string: &UNICODE_STRING) -> Result<OsString> {
+    let mut buffer: Vec<u16> = vec![0; string.Length as usize / 2];
+    if !buffer.is_empty()
+        && ReadProcessMemory(
+            process_handle,
+            string.Buffer as LPCVOID,
+            buffer.as_mut_ptr() as LPVOID,
+            string.Length as usize,
+            null_mut(),
+        ) == 0
+    {
+        bail!(
+            "Failed to read process memory (Win32 error: {})",
+            GetLastError()
+        );
+    }
+    Ok(OsString::from_wide(&buffer))
+}
+
+fn to_u64(file_time: &FILETIME) -> u64 {
+    ((file_time.dwHighDateTime as u64) << 32) | file_time.dwLowDateTime as u64
+}
+
+fn to_screen_rect(rect: &RECT) -> ScreenRect {
+    ScreenRect {
+        left: rect.left,
+        top: rect.top,
+        width: rect.right - rect.left,
+        height: rect.bottom - rect.top,
+    }
+}
+
+/// Adds a monitor handle to the vector of monitor handles
+///
+/// This function is only intended to be used within the `EnumDisplayMonitors` function
+unsafe extern "system" fn enum_monitors_callback(
+    hmonitor: HMONITOR,
+    _hdc: HDC,
+    _rect: LPRECT,
+    raw_hmonitors: LPARAM,
+) -> BOOL {
+    let hmonitors = &mut *(raw_hmonitors as *mut Vec<HMONITOR>);
+    hmonitors.push(hmonitor);
+
+    TRUE
+}
+
+/// Checks a window handle against set criteria, and then adds it to the vector of window handles
+///
+/// This function is only intended to be used within the `EnumWindows` function
+///
+/// # Arguments
+///
+/// * `hwnd` - A window handle
+/// * `raw_hwnds` - A raw representation of the vector of window handles
+unsafe extern "system" fn enum_windows_callback(hwnd: HWND, raw_hwnds: LPARAM) -> BOOL {
+    let hwnds = &mut *(raw_hwnds as *mut Vec<HWND>);
+
+    let mut title: [WCHAR; 2] = zeroed();
+
+    if IsWindowVisible(hwnd) != 0
+        && GetWindowTextW(hwnd, title.as_mut_ptr(), title.len() as i32) != 0
+    {
+        hwnds.push(hwnd);
+    }
+
+    TRUE
+}
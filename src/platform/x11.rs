@@ -0,0 +1,837 @@
+use super::{procfs, CommandLine, WindowId, WindowSystem};
+use crate::data::{MonitorData, ScreenRect, WindowPosition, WindowSize};
+use anyhow::{bail, Context, Result};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+use x11rb::{
+    atom_manager,
+    connection::Connection,
+    protocol::randr::ConnectionExt as _,
+    protocol::xproto::{
+        AtomEnum, ChangeWindowAttributesAux, ClientMessageEvent, ConfigureWindowAux,
+        ConnectionExt as _, EventMask, MapState, Window,
+    },
+    protocol::Event,
+    rust_connection::RustConnection,
+};
+
+atom_manager! {
+    Atoms: AtomsCookie {
+        _NET_SUPPORTING_WM_CHECK,
+        _NET_CLIENT_LIST,
+        _NET_WM_PID,
+        _NET_WM_NAME,
+        _NET_WM_STATE,
+        _NET_WM_STATE_HIDDEN,
+        _NET_WM_STATE_MAXIMIZED_VERT,
+        _NET_WM_STATE_MAXIMIZED_HORZ,
+        _NET_MOVERESIZE_WINDOW,
+        _NET_ACTIVE_WINDOW,
+        _NET_CLOSE_WINDOW,
+        _NET_WORKAREA,
+        UTF8_STRING,
+        WM_CHANGE_STATE,
+        WM_PROTOCOLS,
+        WM_DELETE_WINDOW,
+    }
+}
+
+// _NET_WM_STATE actions
+const STATE_REMOVE: u32 = 0;
+const STATE_ADD: u32 = 1;
+// Source indication for client messages: 2 = pager/direct user action, which window managers honour without focus-stealing checks
+const SOURCE_PAGER: u32 = 2;
+// _NET_MOVERESIZE_WINDOW flags: StaticGravity (so coordinates refer to the client window, as read by `get_rect`) and x/y/width/height present
+const MOVERESIZE_FLAGS: u32 =
+    10 | (1 << 8) | (1 << 9) | (1 << 10) | (1 << 11) | (SOURCE_PAGER << 12);
+// ICCCM WM_CHANGE_STATE value for minimizing
+const ICONIC_STATE: u32 = 3;
+// How often queued events are checked while waiting for a window event
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// An X11 window system, driven through EWMH hints when a compliant window manager is running
+///
+/// Without a window manager (for example, a bare Xvfb server) windows are found and moved directly, so the backend still works in headless environments
+pub struct X11WindowSystem {
+    connection: RustConnection,
+    screen_number: usize,
+    root: Window,
+    atoms: Atoms,
+    ewmh: bool,
+}
+
+impl X11WindowSystem {
+    /// Connects to the X server named by the `DISPLAY` environment variable
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection could not be established
+    pub fn connect() -> Result<Self> {
+        Self::connect_to(None)
+    }
+
+    /// Connects to an X server by display name (such as `:1`), or to the one named by `DISPLAY` if `None`
+    fn connect_to(display_name: Option<&str>) -> Result<Self> {
+        let (connection, screen_number) =
+            x11rb::connect(display_name).with_context(|| "Failed to connect to X server")?;
+        let root = connection.setup().roots[screen_number].root;
+        let atoms = Atoms::new(&connection)?
+            .reply()
+            .with_context(|| "Failed to intern X11 atoms")?;
+
+        let mut window_system = X11WindowSystem {
+            connection,
+            screen_number,
+            root,
+            atoms,
+            ewmh: false,
+        };
+        window_system.ewmh = !window_system
+            .get_property32(root, atoms._NET_SUPPORTING_WM_CHECK, AtomEnum::WINDOW)?
+            .is_empty();
+
+        // Listen for top-level windows being mapped or destroyed, and for changes to the client list, so waits can end early
+        window_system
+            .connection
+            .change_window_attributes(
+                root,
+                &ChangeWindowAttributesAux::new()
+                    .event_mask(EventMask::SUBSTRUCTURE_NOTIFY | EventMask::PROPERTY_CHANGE),
+            )?
+            .check()
+            .with_context(|| "Failed to listen for window events")?;
+
+        Ok(window_system)
+    }
+
+    fn get_property32(
+        &self,
+        window: Window,
+        property: u32,
+        property_type: impl Into<u32>,
+    ) -> Result<Vec<u32>> {
+        let reply = self
+            .connection
+            .get_property(false, window, property, property_type, 0, u32::MAX)?
+            .reply()
+            .with_context(|| format!("Failed to read property of window {}", window))?;
+        let values = match reply.value32() {
+            Some(values) => values.collect(),
+            None => Vec::new(),
+        };
+        Ok(values)
+    }
+
+    /// Gets the ID of the process that owns a window, from its `_NET_WM_PID` property
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the property could not be read or is not set
+    pub fn get_pid(&self, window: WindowId) -> Result<u32> {
+        match self
+            .get_property32(
+                window.0 as Window,
+                self.atoms._NET_WM_PID,
+                AtomEnum::CARDINAL,
+            )?
+            .first()
+        {
+            Some(pid) => Ok(*pid),
+            None => bail!("Window {} does not set _NET_WM_PID", window.0),
+        }
+    }
+
+    fn get_state(&self, window: Window) -> Result<Vec<u32>> {
+        self.get_property32(window, self.atoms._NET_WM_STATE, AtomEnum::ATOM)
+    }
+
+    fn send_message(&self, window: Window, message_type: u32, data: [u32; 5]) -> Result<()> {
+        let event = ClientMessageEvent::new(32, window, message_type, data);
+        self.connection.send_event(
+            false,
+            self.root,
+            EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+            event,
+        )?;
+        self.connection.flush()?;
+        Ok(())
+    }
+
+    fn set_maximized(&self, window: Window, maximized: bool) -> Result<()> {
+        self.send_message(
+            window,
+            self.atoms._NET_WM_STATE,
+            [
+                if maximized { STATE_ADD } else { STATE_REMOVE },
+                self.atoms._NET_WM_STATE_MAXIMIZED_VERT,
+                self.atoms._NET_WM_STATE_MAXIMIZED_HORZ,
+                SOURCE_PAGER,
+                0,
+            ],
+        )
+    }
+}
+
+impl WindowSystem for X11WindowSystem {
+    fn get_open_windows(&self) -> Result<Vec<WindowId>> {
+        let candidates = if self.ewmh {
+            self.get_property32(self.root, self.atoms._NET_CLIENT_LIST, AtomEnum::WINDOW)?
+        } else {
+            let mut viewable = Vec::new();
+            for window in self.connection.query_tree(self.root)?.reply()?.children {
+                let attributes = match self.connection.get_window_attributes(window)?.reply() {
+                    Ok(data) => data,
+                    Err(_) => continue, // Destroyed since the tree was queried
+                };
+                if attributes.map_state == MapState::VIEWABLE && !attributes.override_redirect {
+                    viewable.push(window);
+                }
+            }
+            viewable
+        };
+
+        // Windows can be destroyed while they are being listed (and the client list can be stale), so those whose title cannot be read are skipped rather than failing the whole list
+        let mut windows = Vec::new();
+        for window in candidates {
+            if let Ok(title) = self.get_title(WindowId(window as u64)) {
+                if !title.is_empty() {
+                    windows.push(WindowId(window as u64));
+                }
+            }
+        }
+
+        Ok(windows)
+    }
+
+    fn get_module_path(&self, window: WindowId) -> Result<String> {
+        procfs::get_module_path(self.get_pid(window)?)
+    }
+
+    fn get_command_line(&self, window: WindowId) -> Result<Option<CommandLine>> {
+        Ok(Some(procfs::get_command_line(self.get_pid(window)?)?))
+    }
+
+    fn get_process_age(&self, window: WindowId) -> Result<Option<Duration>> {
+        Ok(Some(procfs::get_process_age(self.get_pid(window)?)?))
+    }
+
+    fn get_title(&self, window: WindowId) -> Result<String> {
+        let window = window.0 as Window;
+        let reply = self
+            .connection
+            .get_property(
+                false,
+                window,
+                self.atoms._NET_WM_NAME,
+                self.atoms.UTF8_STRING,
+                0,
+                u32::MAX,
+            )?
+            .reply()?;
+        if !reply.value.is_empty() {
+            return Ok(String::from_utf8_lossy(&reply.value).to_string());
+        }
+
+        let reply = self
+            .connection
+            .get_property(
+                false,
+                window,
+                AtomEnum::WM_NAME,
+                AtomEnum::STRING,
+                0,
+                u32::MAX,
+            )?
+            .reply()?;
+        Ok(String::from_utf8_lossy(&reply.value).to_string())
+    }
+
+    fn get_class(&self, window: WindowId) -> Result<Option<String>> {
+        let reply = self
+            .connection
+            .get_property(
+                false,
+                window.0 as Window,
+                AtomEnum::WM_CLASS,
+                AtomEnum::STRING,
+                0,
+                u32::MAX,
+            )?
+            .reply()?;
+
+        // WM_CLASS holds the instance name then the class name, each null-terminated
+        Ok(reply
+            .value
+            .split(|byte| *byte == 0)
+            .nth(1)
+            .filter(|class| !class.is_empty())
+            .map(|class| String::from_utf8_lossy(class).to_string()))
+    }
+
+    fn get_monitors(&self) -> Result<Vec<MonitorData>> {
+        // _NET_WORKAREA holds one rectangle per desktop, covering every monitor; they are usually all the same
+        let work_area = match self
+            .get_property32(self.root, self.atoms._NET_WORKAREA, AtomEnum::CARDINAL)?
+            .as_slice()
+        {
+            [left, top, width, height, ..] => Some(ScreenRect {
+                left: *left as i32,
+                top: *top as i32,
+                width: *width as i32,
+                height: *height as i32,
+            }),
+            _ => None,
+        };
+
+        let randr_monitors = match self.connection.randr_get_monitors(self.root, true) {
+            Ok(cookie) => cookie
+                .reply()
+                .map(|reply| reply.monitors)
+                .unwrap_or_default(),
+            Err(_) => Vec::new(), // RandR is not supported by this X server
+        };
+
+        let mut monitors = Vec::new();
+        for monitor in randr_monitors {
+            let name = self.connection.get_atom_name(monitor.name)?.reply()?.name;
+            let bounds = ScreenRect {
+                left: monitor.x as i32,
+                top: monitor.y as i32,
+                width: monitor.width as i32,
+                height: monitor.height as i32,
+            };
+            monitors.push(MonitorData {
+                id: String::from_utf8_lossy(&name).to_string(),
+                bounds,
+                work_area: intersect(&bounds, work_area.as_ref()),
+                scale: 1.0,
+                primary: monitor.primary,
+            });
+        }
+
+        if monitors.is_empty() {
+            let screen = &self.connection.setup().roots[self.screen_number];
+            let bounds = ScreenRect {
+                left: 0,
+                top: 0,
+                width: screen.width_in_pixels as i32,
+                height: screen.height_in_pixels as i32,
+            };
+            monitors.push(MonitorData {
+                id: format!("screen-{}", self.screen_number),
+                bounds,
+                work_area: intersect(&bounds, work_area.as_ref()),
+                scale: 1.0,
+                primary: true,
+            });
+        }
+
+        Ok(monitors)
+    }
+
+    fn get_rect(&self, window: WindowId) -> Result<(WindowPosition, WindowSize)> {
+        let window = window.0 as Window;
+        let geometry = self
+            .connection
+            .get_geometry(window)?
+            .reply()
+            .with_context(|| "Failed to get window geometry")?;
+        let origin = self
+            .connection
+            .translate_coordinates(window, self.root, 0, 0)?
+            .reply()
+            .with_context(|| "Failed to translate window coordinates")?;
+
+        Ok((
+            WindowPosition {
+                top: origin.dst_y as i32,
+                left: origin.dst_x as i32,
+            },
+            WindowSize {
+                width: geometry.width as i32,
+                height: geometry.height as i32,
+            },
+        ))
+    }
+
+    fn is_minimized(&self, window: WindowId) -> Result<bool> {
+        if !self.ewmh {
+            let attributes = self
+                .connection
+                .get_window_attributes(window.0 as Window)?
+                .reply()?;
+            return Ok(attributes.map_state != MapState::VIEWABLE);
+        }
+
+        Ok(self
+            .get_state(window.0 as Window)?
+            .contains(&self.atoms._NET_WM_STATE_HIDDEN))
+    }
+
+    fn is_maximized(&self, window: WindowId) -> Result<bool> {
+        let state = self.get_state(window.0 as Window)?;
+        Ok(state.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_VERT)
+            && state.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_HORZ))
+    }
+
+    fn reposition_and_resize(
+        &self,
+        window: WindowId,
+        position: &WindowPosition,
+        size: &WindowSize,
+    ) -> Result<()> {
+        if self.ewmh {
+            return self.send_message(
+                window.0 as Window,
+                self.atoms._NET_MOVERESIZE_WINDOW,
+                [
+                    MOVERESIZE_FLAGS,
+                    position.left as u32,
+                    position.top as u32,
+                    size.width as u32,
+                    size.height as u32,
+                ],
+            );
+        }
+
+        self.connection.configure_window(
+            window.0 as Window,
+            &ConfigureWindowAux::new()
+                .x(position.left)
+                .y(position.top)
+                .width(size.width as u32)
+                .height(size.height as u32),
+        )?;
+        self.connection.flush()?;
+        Ok(())
+    }
+
+    fn restore(&self, window: WindowId) -> Result<()> {
+        // A minimized window is only shown again, so that it keeps its maximized state (as `capture_arrangement` reads it afterwards)
+        if self.ewmh && !self.is_minimized(window)? {
+            return self.set_maximized(window.0 as Window, false);
+        }
+
+        self.connection.map_window(window.0 as Window)?;
+        self.connection.flush()?;
+
+        if self.ewmh {
+            self.send_message(
+                window.0 as Window,
+                self.atoms._NET_ACTIVE_WINDOW,
+                [SOURCE_PAGER, 0, 0, 0, 0],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn minimize(&self, window: WindowId) -> Result<()> {
+        if !self.ewmh {
+            self.connection.unmap_window(window.0 as Window)?;
+            self.connection.flush()?;
+            return Ok(());
+        }
+
+        self.send_message(
+            window.0 as Window,
+            self.atoms.WM_CHANGE_STATE,
+            [ICONIC_STATE, 0, 0, 0, 0],
+        )
+    }
+
+    fn maximize(&self, window: WindowId) -> Result<()> {
+        self.set_maximized(window.0 as Window, true)
+    }
+
+    fn close(&self, window: WindowId) -> Result<()> {
+        if self.ewmh {
+            return self.send_message(
+                window.0 as Window,
+                self.atoms._NET_CLOSE_WINDOW,
+                [0, SOURCE_PAGER, 0, 0, 0],
+            );
+        }
+
+        let event = ClientMessageEvent::new(
+            32,
+            window.0 as Window,
+            self.atoms.WM_PROTOCOLS,
+            [self.atoms.WM_DELETE_WINDOW, 0, 0, 0, 0],
+        );
+        self.connection
+            .send_event(false, window.0 as Window, EventMask::NO_EVENT, event)?;
+        self.connection.flush()?;
+        Ok(())
+    }
+
+    fn wait_for_window_event(&self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            // Events are read without blocking, as the connection cannot wait for one with a timeout
+            loop {
+                let event = match self.connection.poll_for_event() {
+                    Ok(Some(event)) => event,
+                    Ok(None) => break,
+                    Err(_) => {
+                        thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                        return;
+                    }
+                };
+                let is_window_event = match event {
+                    Event::MapNotify(_) | Event::UnmapNotify(_) | Event::DestroyNotify(_) => true,
+                    Event::PropertyNotify(event) => event.atom == self.atoms._NET_CLIENT_LIST,
+                    _ => false,
+                };
+                if is_window_event {
+                    return;
+                }
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return;
+            }
+            thread::sleep((deadline - now).min(EVENT_POLL_INTERVAL));
+        }
+    }
+}
+
+/// Gets the part of a monitor's bounds inside the work area (or the whole monitor if there is no work area, or they do not overlap)
+fn intersect(bounds: &ScreenRect, work_area: Option<&ScreenRect>) -> ScreenRect {
+    let work_area = match work_area {
+        Some(data) => data,
+        None => return *bounds,
+    };
+
+    let left = bounds.left.max(work_area.left);
+    let top = bounds.top.max(work_area.top);
+    let right = (bounds.left + bounds.width).min(work_area.left + work_area.width);
+    let bottom = (bounds.top + bounds.height).min(work_area.top + work_area.height);
+    if right <= left || bottom <= top {
+        return *bounds;
+    }
+
+    ScreenRect {
+        left,
+        top,
+        width: right - left,
+        height: bottom - top,
+    }
+}
+
+/// These need `Xvfb` to be installed, so are only run with `cargo test -- --ignored`
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader},
+        process::{self, Child, Command, Stdio},
+    };
+    use x11rb::{
+        protocol::xproto::{CreateWindowAux, PropMode, WindowClass},
+        wrapper::ConnectionExt as _,
+        COPY_DEPTH_FROM_PARENT,
+    };
+
+    /// An Xvfb server on a display of its own, stopped when dropped
+    struct Xvfb {
+        process: Child,
+        display: String,
+    }
+
+    impl Xvfb {
+        fn start() -> Self {
+            // Xvfb picks a free display and writes its number to stdout
+            let mut process = Command::new("Xvfb")
+                .args(["-displayfd", "1", "-nolisten", "tcp"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .expect("failed to start Xvfb");
+            let mut display_number = String::new();
+            BufReader::new(process.stdout.take().unwrap())
+                .read_line(&mut display_number)
+                .unwrap();
+            Xvfb {
+                process,
+                display: format!(":{}", display_number.trim()),
+            }
+        }
+
+        fn connect(&self) -> (RustConnection, Window, Atoms) {
+            let (connection, screen_number) = x11rb::connect(Some(&self.display)).unwrap();
+            let root = connection.setup().roots[screen_number].root;
+            let atoms = Atoms::new(&connection).unwrap().reply().unwrap();
+            (connection, root, atoms)
+        }
+    }
+
+    impl Drop for Xvfb {
+        fn drop(&mut self) {
+            let _ = self.process.kill();
+            let _ = self.process.wait();
+        }
+    }
+
+    /// Runs a window manager that supports just enough of EWMH for the backend: it keeps `_NET_CLIENT_LIST` (without ever removing destroyed windows, so the list can be stale) and `_NET_WM_STATE`, and minimizes by unmapping
+    ///
+    /// It stops when the X server does
+    fn start_window_manager(xvfb: &Xvfb) {
+        let (connection, root, atoms) = xvfb.connect();
+        connection
+            .change_window_attributes(
+                root,
+                &ChangeWindowAttributesAux::new()
+                    .event_mask(EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY),
+            )
+            .unwrap()
+            .check()
+            .unwrap();
+        connection
+            .change_property32(
+                PropMode::REPLACE,
+                root,
+                atoms._NET_SUPPORTING_WM_CHECK,
+                AtomEnum::WINDOW,
+                &[root],
+            )
+            .unwrap();
+        connection.sync().unwrap();
+
+        thread::spawn(move || {
+            let mut client_list: Vec<Window> = Vec::new();
+            while let Ok(event) = connection.wait_for_event() {
+                match event {
+                    Event::MapRequest(event) => {
+                        let _ = connection.map_window(event.window);
+                        update_state(
+                            &connection,
+                            &atoms,
+                            event.window,
+                            STATE_REMOVE,
+                            &[atoms._NET_WM_STATE_HIDDEN],
+                        );
+                        if !client_list.contains(&event.window) {
+                            client_list.push(event.window);
+                        }
+                        let _ = connection.change_property32(
+                            PropMode::REPLACE,
+                            root,
+                            atoms._NET_CLIENT_LIST,
+                            AtomEnum::WINDOW,
+                            &client_list,
+                        );
+                    }
+                    Event::ClientMessage(event) => {
+                        let data = event.data.as_data32();
+                        if event.type_ == atoms._NET_WM_STATE {
+                            update_state(&connection, &atoms, event.window, data[0], &data[1..3]);
+                        } else if event.type_ == atoms.WM_CHANGE_STATE && data[0] == ICONIC_STATE {
+                            let _ = connection.unmap_window(event.window);
+                            update_state(
+                                &connection,
+                                &atoms,
+                                event.window,
+                                STATE_ADD,
+                                &[atoms._NET_WM_STATE_HIDDEN],
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+                let _ = connection.flush();
+            }
+        });
+    }
+
+    /// Adds or removes atoms from a window's `_NET_WM_STATE`
+    fn update_state(
+        connection: &RustConnection,
+        atoms: &Atoms,
+        window: Window,
+        action: u32,
+        changes: &[u32],
+    ) {
+        let mut state: Vec<u32> = connection
+            .get_property(
+                false,
+                window,
+                atoms._NET_WM_STATE,
+                AtomEnum::ATOM,
+                0,
+                u32::MAX,
+            )
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| reply.value32().map(|values| values.collect()))
+            .unwrap_or_default();
+        for change in changes.iter().filter(|change| **change != 0) {
+            state.retain(|atom| atom != change);
+            if action == STATE_ADD {
+                state.push(*change);
+            }
+        }
+        let _ = connection.change_property32(
+            PropMode::REPLACE,
+            window,
+            atoms._NET_WM_STATE,
+            AtomEnum::ATOM,
+            &state,
+        );
+    }
+
+    /// Opens a top-level window owned by this process, as an application would
+    fn open_window(
+        connection: &RustConnection,
+        root: Window,
+        atoms: &Atoms,
+        title: &str,
+    ) -> Window {
+        let window = connection.generate_id().unwrap();
+        connection
+            .create_window(
+                COPY_DEPTH_FROM_PARENT,
+                window,
+                root,
+                10,
+                20,
+                300,
+                200,
+                0,
+                WindowClass::INPUT_OUTPUT,
+                0,
+                &CreateWindowAux::new(),
+            )
+            .unwrap();
+        connection
+            .change_property8(
+                PropMode::REPLACE,
+                window,
+                atoms._NET_WM_NAME,
+                atoms.UTF8_STRING,
+                title.as_bytes(),
+            )
+            .unwrap();
+        connection
+            .change_property32(
+                PropMode::REPLACE,
+                window,
+                atoms._NET_WM_PID,
+                AtomEnum::CARDINAL,
+                &[process::id()],
+            )
+            .unwrap();
+        connection.map_window(window).unwrap();
+        connection.sync().unwrap();
+        window
+    }
+
+    /// Waits for the window manager (or X server) to catch up, failing the test if `condition` is still false after a few seconds
+    fn wait_until(description: &str, mut condition: impl FnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(
+                Instant::now() < deadline,
+                "timed out waiting until {}",
+                description
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    #[ignore = "needs Xvfb"]
+    fn arranges_windows_without_window_manager() {
+        let xvfb = Xvfb::start();
+        let (connection, root, atoms) = xvfb.connect();
+        let window_system = X11WindowSystem::connect_to(Some(&xvfb.display)).unwrap();
+        assert!(!window_system.ewmh);
+
+        let window = WindowId(open_window(&connection, root, &atoms, "Editor") as u64);
+        assert_eq!(window_system.get_open_windows().unwrap(), [window]);
+        assert_eq!(window_system.get_title(window).unwrap(), "Editor");
+        assert_eq!(
+            window_system.get_module_path(window).unwrap(),
+            std::env::current_exe().unwrap().to_string_lossy()
+        );
+
+        let position = WindowPosition {
+            top: 100,
+            left: 200,
+        };
+        let size = WindowSize {
+            width: 640,
+            height: 480,
+        };
+        window_system
+            .reposition_and_resize(window, &position, &size)
+            .unwrap();
+        wait_until("the window is moved", || {
+            window_system.get_rect(window).unwrap() == (position, size)
+        });
+
+        window_system.minimize(window).unwrap();
+        wait_until("the window is minimized", || {
+            window_system.is_minimized(window).unwrap()
+        });
+        window_system.restore(window).unwrap();
+        wait_until("the window is restored", || {
+            !window_system.is_minimized(window).unwrap()
+        });
+    }
+
+    #[test]
+    #[ignore = "needs Xvfb"]
+    fn skips_destroyed_windows_in_client_list() {
+        let xvfb = Xvfb::start();
+        start_window_manager(&xvfb);
+        let (connection, root, atoms) = xvfb.connect();
+        let window_system = X11WindowSystem::connect_to(Some(&xvfb.display)).unwrap();
+        assert!(window_system.ewmh);
+
+        let editor = WindowId(open_window(&connection, root, &atoms, "Editor") as u64);
+        let closed = open_window(&connection, root, &atoms, "Closed");
+        wait_until("both windows are listed", || {
+            window_system.get_open_windows().unwrap().len() == 2
+        });
+        connection.destroy_window(closed).unwrap();
+        connection.sync().unwrap();
+
+        assert_eq!(window_system.get_open_windows().unwrap(), [editor]);
+    }
+
+    #[test]
+    #[ignore = "needs Xvfb"]
+    fn restoring_minimized_window_keeps_it_maximized() {
+        let xvfb = Xvfb::start();
+        start_window_manager(&xvfb);
+        let (connection, root, atoms) = xvfb.connect();
+        let window_system = X11WindowSystem::connect_to(Some(&xvfb.display)).unwrap();
+
+        let window = WindowId(open_window(&connection, root, &atoms, "Editor") as u64);
+        window_system.maximize(window).unwrap();
+        wait_until("the window is maximized", || {
+            window_system.is_maximized(window).unwrap()
+        });
+        window_system.minimize(window).unwrap();
+        wait_until("the window is minimized", || {
+            window_system.is_minimized(window).unwrap()
+        });
+
+        // As `capture_arrangement` does, to read the details of a minimized window
+        window_system.restore(window).unwrap();
+        wait_until("the window is shown again", || {
+            !window_system.is_minimized(window).unwrap()
+        });
+        assert!(window_system.is_maximized(window).unwrap());
+
+        // As `place_window` does, so that the window can be moved
+        window_system.restore(window).unwrap();
+        wait_until("the window is un-maximized", || {
+            !window_system.is_maximized(window).unwrap()
+        });
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{
+    config::{WindowFacts, WindowRule},
+    data::WindowDataEntry,
+};
+use anyhow::{Context, Result};
+
+/// Applies the rules whose filters match an arrangement entry to it, in order (so later rules override earlier ones)
+///
+/// # Arguments
+///
+/// * `entry` - The entry to apply the rules to
+/// * `rules` - The rules to apply
+///
+/// # Errors
+///
+/// Returns an error if the filter of one of the rules is invalid
+pub fn apply_window_rules(entry: &mut WindowDataEntry, rules: &[WindowRule]) -> Result<()> {
+    for (index, rule) in rules.iter().enumerate() {
+        let facts = WindowFacts {
+            module_path: &entry.application_path,
+            title: entry.window_title.as_deref().unwrap_or_default(),
+            class: entry.window_class.as_deref(),
+            monitor: entry.monitor.as_ref().map(|monitor| monitor.id.as_str()),
+            size: entry.size,
+            minimized: entry.minimized,
+        };
+        if !rule
+            .filter
+            .matches(&facts)
+            .with_context(|| format!("Invalid filter in rule {}", index))?
+        {
+            continue;
+        }
+
+        if let Some(launch) = rule.launch {
+            entry.launch = launch;
+        }
+        if let Some(reposition) = rule.reposition {
+            entry.reposition = reposition;
+        }
+        if let Some(default_args) = &rule.default_args {
+            if entry.application_args.is_empty() {
+                entry.application_args = default_args.clone();
+            }
+        }
+        if let Some(launch_command) = &rule.launch_command {
+            entry.launch_command = Some(launch_command.clone());
+        }
+
+        // A pinned geometry is absolute, so it is not remapped when monitors change
+        if rule.position.is_some() || rule.size.is_some() {
+            entry.position = rule.position.unwrap_or(entry.position);
+            entry.size = rule.size.unwrap_or(entry.size);
+            entry.maximised = false;
+            entry.monitor = None;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{MonitorPlacement, WindowPosition, WindowSize};
+
+    fn entry() -> WindowDataEntry {
+        let position = WindowPosition { top: 50, left: 100 };
+        let size = WindowSize {
+            width: 800,
+            height: 600,
+        };
+        WindowDataEntry {
+            application_path: String::from("/usr/bin/firefox"),
+            application_args: Vec::new(),
+            launch_command: None,
+            working_directory: None,
+            launch: true,
+            reposition: true,
+            position,
+            size,
+            minimized: false,
+            maximised: true,
+            window_title: Some(String::from("Inbox - Mozilla Firefox")),
+            title_pattern: None,
+            window_class: Some(String::from("firefox")),
+            instance: None,
+            workspace: None,
+            monitor: Some(MonitorPlacement {
+                id: String::from("DP-1"),
+                position,
+                size,
+            }),
+            timeout: None,
+            ready_when: None,
+        }
+    }
+
+    fn rules(json: &str) -> Vec<WindowRule> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn matching_rule_overrides_entry() {
+        let mut entry = entry();
+
+        apply_window_rules(
+            &mut entry,
+            &rules(
+                r#"[{
+                    "exe": "*/firefox",
+                    "title": "Inbox",
+                    "launch": false,
+                    "reposition": false,
+                    "default_args": ["--private-window"],
+                    "launch_command": ["/usr/bin/flatpak", "run", "org.mozilla.firefox"],
+                    "position": {"top": 0, "left": 0},
+                    "size": {"width": 1280, "height": 720}
+                }]"#,
+            ),
+        )
+        .unwrap();
+
+        assert!(!entry.launch);
+        assert!(!entry.reposition);
+        assert_eq!(entry.application_args, ["--private-window"]);
+        assert_eq!(
+            entry.launch_command.unwrap(),
+            ["/usr/bin/flatpak", "run", "org.mozilla.firefox"]
+        );
+        assert_eq!(entry.position, WindowPosition { top: 0, left: 0 });
+        assert_eq!(
+            entry.size,
+            WindowSize {
+                width: 1280,
+                height: 720
+            }
+        );
+        // A pinned geometry is not maximised or remapped
+        assert!(!entry.maximised);
+        assert!(entry.monitor.is_none());
+    }
+
+    #[test]
+    fn rule_that_does_not_match_leaves_entry_alone() {
+        let mut entry = entry();
+
+        apply_window_rules(
+            &mut entry,
+            &rules(
+                r#"[
+                    {"exe": "*/thunderbird", "launch": false},
+                    {"exe": "*/firefox", "title": "Drafts", "position": {"top": 0, "left": 0}}
+                ]"#,
+            ),
+        )
+        .unwrap();
+
+        assert!(entry.launch);
+        assert_eq!(entry.position, WindowPosition { top: 50, left: 100 });
+        assert!(entry.maximised);
+        assert!(entry.monitor.is_some());
+    }
+
+    #[test]
+    fn default_args_do_not_replace_saved_args() {
+        let mut entry = entry();
+        entry.application_args = vec![String::from("--new-window")];
+
+        apply_window_rules(
+            &mut entry,
+            &rules(r#"[{"class": "Firefox", "default_args": ["--private-window"]}]"#),
+        )
+        .unwrap();
+
+        assert_eq!(entry.application_args, ["--new-window"]);
+    }
+
+    #[test]
+    fn later_rules_override_earlier_ones() {
+        let mut entry = entry();
+
+        apply_window_rules(
+            &mut entry,
+            &rules(r#"[{"exe": "*", "launch": false}, {"exe": "*/firefox", "launch": true}]"#),
+        )
+        .unwrap();
+
+        assert!(entry.launch);
+    }
+
+    #[test]
+    fn invalid_filter_is_an_error() {
+        let mut entry = entry();
+
+        let result = apply_window_rules(&mut entry, &rules(r#"[{"title": "(", "launch": false}]"#));
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    config::{WindowFacts, WindowFilters},
+    data::{WindowData, WindowDataEntry, CURRENT_VERSION},
+    platform::{CommandLine, WindowId, WindowSystem},
+    printwarning,
+    utils::{
+        filter_open_windows, find_monitor, get_monitor_placement, get_monitors,
+        get_open_window_details,
+    },
+    verbose, CONFIG,
+};
+use anyhow::{Context, Result};
+
+/// Captures the current arrangement of open windows
+///
+/// # Arguments
+///
+/// * `window_system` - The window system to capture windows from
+/// * `name` - The name to give the arrangement
+/// * `filters` - Which windows to capture
+/// * `restore_minimized` - Whether minimized windows are briefly restored (and then minimized again) so that their position and size can be read - if not, they are left alone, and their position and size are whatever the window system reports for them while minimized
+///
+/// # Errors
+///
+/// Returns an error if the open windows could not be enumerated, the state of one of them could not be read, or one of the filters is invalid
+///
+/// # Returns
+///
+/// The arrangement, and the window each of its entries was captured from (in the same order)
+pub fn capture_arrangement(
+    window_system: &dyn WindowSystem,
+    name: String,
+    filters: &WindowFilters,
+    restore_minimized: bool,
+) -> Result<(WindowData, Vec<WindowId>)> {
+    let open_windows = window_system.get_open_windows()?;
+    let mut open_window_details = get_open_window_details(window_system, &open_windows);
+    let monitors = get_monitors(window_system);
+
+    // Filters that do not need a window's geometry are applied up front, so that excluded minimized windows are never restored
+    let needs_geometry = filters.needs_geometry();
+    if !needs_geometry {
+        filter_open_windows(window_system, &mut open_window_details, filters, &monitors)?;
+    }
+
+    let mut window_data = Vec::new();
+    let mut windows = Vec::new();
+
+    for open_window in open_window_details {
+        let window = open_window.window;
+
+        let is_minimized = window_system.is_minimized(window)?;
+        let is_restored = is_minimized && restore_minimized;
+        restore_window(window_system, window, is_restored)?; // For properly checking details when not minimized
+        let is_maximized = window_system.is_maximized(window)?;
+
+        let rect_result = window_system.get_rect(window);
+        let workspace_result = window_system.get_workspace(window);
+        minimize_window(window_system, window, is_restored)?; // Restore window to its previous (minimized) state
+        let (position, size) = rect_result?;
+        let workspace = workspace_result?;
+        let monitor = find_monitor(&monitors, &position, &size);
+
+        if needs_geometry
+            && !filters.is_included(&WindowFacts {
+                module_path: &open_window.module_path,
+                title: &open_window.title,
+                class: open_window.class.as_deref(),
+                monitor: monitor.map(|monitor| monitor.id.as_str()),
+                size,
+                minimized: is_minimized,
+            })?
+        {
+            continue;
+        }
+
+        let command_line = if CONFIG.capture_args {
+            get_command_line(window_system, window)
+        } else {
+            CommandLine::default()
+        };
+
+        window_data.push(WindowDataEntry {
+            application_path: open_window.module_path,
+            application_args: command_line.args,
+            launch_command: None,
+            working_directory: command_line.working_directory,
+            launch: true,
+            reposition: true,
+            position,
+            size,
+            minimized: is_minimized,
+            maximised: is_maximized,
+            window_title: Some(open_window.title),
+            title_pattern: None,
+            window_class: open_window.class,
+            instance: Some(open_window.instance),
+            workspace,
+            monitor: monitor.map(|monitor| get_monitor_placement(monitor, &position, &size)),
+            timeout: None,
+            ready_when: None,
+        });
+        windows.push(window);
+    }
+
+    let data = WindowData {
+        version: CURRENT_VERSION,
+        name,
+        description: None,
+        tags: Vec::new(),
+        created_at: None,
+        updated_at: None,
+        created_on_host: None,
+        windough_version: None,
+        data: window_data,
+        monitors,
+        settings: None,
+    };
+
+    Ok((data, windows))
+}
+
+/// Gets the arguments and working directory a window's process was started with, falling back to none (with a warning in verbose mode) if they cannot be read
+fn get_command_line(window_system: &dyn WindowSystem, window: WindowId) -> CommandLine {
+    match window_system
+        .get_command_line(window)
+        .with_context(|| "failed to get command line of window's process")
+    {
+        Ok(command_line) => command_line.unwrap_or_default(),
+        Err(error) => {
+            if verbose() {
+                printwarning!("{:#}", error);
+            }
+            CommandLine::default()
+        }
+    }
+}
+
+fn restore_window(
+    window_system: &dyn WindowSystem,
+    window: WindowId,
+    is_minimized: bool,
+) -> Result<()> {
+    if is_minimized {
+        window_system.restore(window)?;
+    }
+    Ok(())
+}
+fn minimize_window(
+    window_system: &dyn WindowSystem,
+    window: WindowId,
+    is_minimized: bool,
+) -> Result<()> {
+    if is_minimized {
+        window_system.minimize(window)?;
+    }
+    Ok(())
+}
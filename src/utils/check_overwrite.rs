@@ -0,0 +1,34 @@
+use crate::{data::get_window_data_path, utils::confirm};
+use anyhow::{bail, Result};
+
+/// Checks that an arrangement can be written, asking the user first if it would overwrite an existing one
+///
+/// # Arguments
+///
+/// * `name` - The name of the arrangement that will be written (which should already be validated)
+/// * `force` - Whether existing arrangements are overwritten without asking
+///
+/// # Errors
+///
+/// Returns an error if the arrangement exists and the user chose not to overwrite it, or there is no terminal to ask on and `force` is not set
+///
+/// # Returns
+///
+/// Whether the arrangement already exists
+pub fn check_overwrite(name: &str, force: bool) -> Result<bool> {
+    let exists = get_window_data_path(name).is_file();
+    if exists && !force {
+        match confirm(&format!(
+            "Arrangement '{}' already exists. Overwrite it?",
+            name
+        ))? {
+            Some(true) => (),
+            Some(false) => bail!("Arrangement '{}' was not overwritten", name),
+            None => bail!(
+                "Arrangement '{}' already exists - use --force to overwrite it",
+                name
+            ),
+        }
+    }
+    Ok(exists)
+}
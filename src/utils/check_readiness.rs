@@ -0,0 +1,90 @@
+use crate::{
+    data::{WindowPosition, WindowReadiness, WindowSize},
+    platform::{WindowId, WindowSystem},
+    utils::OpenWindow,
+};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::{collections::HashMap, time::Duration, time::Instant};
+
+/// When each window was first seen at its current position and size
+pub type GeometryHistory = HashMap<WindowId, ((WindowPosition, WindowSize), Instant)>;
+
+/// Checks whether a window meets an entry's readiness conditions
+///
+/// # Arguments
+///
+/// * `window_system` - The window system the window belongs to
+/// * `open_window` - The window to check
+/// * `readiness` - The conditions the window must meet
+/// * `geometry_history` - Where the window's geometry has been seen before, which is updated
+///
+/// # Errors
+///
+/// Returns an error if the title pattern is not a valid regular expression, or the window's geometry could not be read
+///
+/// # Returns
+///
+/// `None` if the window is ready, otherwise the first condition it does not meet
+pub fn check_readiness(
+    window_system: &dyn WindowSystem,
+    open_window: &OpenWindow,
+    readiness: &WindowReadiness,
+    geometry_history: &mut GeometryHistory,
+) -> Result<Option<String>> {
+    if let Some(title_pattern) = &readiness.title_pattern {
+        let pattern = Regex::new(title_pattern)
+            .with_context(|| format!("Invalid readiness title pattern '{}'", title_pattern))?;
+        if !pattern.is_match(&open_window.title) {
+            return Ok(Some(format!(
+                "title '{}' does not match '{}'",
+                open_window.title, title_pattern
+            )));
+        }
+    }
+
+    let geometry = window_system.get_rect(open_window.window)?;
+    let now = window_system.now();
+    let first_seen = match geometry_history.get(&open_window.window) {
+        Some((previous_geometry, first_seen)) if *previous_geometry == geometry => *first_seen,
+        _ => {
+            geometry_history.insert(open_window.window, (geometry, now));
+            now
+        }
+    };
+
+    let (_, size) = geometry;
+    if let Some(min_size) = &readiness.min_size {
+        if size.width < min_size.width || size.height < min_size.height {
+            return Ok(Some(format!(
+                "size {}x{} is smaller than {}x{}",
+                size.width, size.height, min_size.width, min_size.height
+            )));
+        }
+    }
+
+    if let Some(stable_for) = readiness.stable_for {
+        let stable_for = Duration::from_millis(stable_for as u64);
+        if now - first_seen < stable_for {
+            return Ok(Some(format!(
+                "geometry has not been stable for {}ms",
+                stable_for.as_millis()
+            )));
+        }
+    }
+
+    if let Some(process_age) = readiness.process_age {
+        let process_age = Duration::from_secs(process_age as u64);
+        // Backends that cannot tell a process's age do not hold windows back
+        if let Some(age) = window_system.get_process_age(open_window.window)? {
+            if age < process_age {
+                return Ok(Some(format!(
+                    "process has been running for less than {}s",
+                    process_age.as_secs()
+                )));
+            }
+        }
+    }
+
+    Ok(None)
+}
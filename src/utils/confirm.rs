@@ -0,0 +1,37 @@
+use anyhow::{Context, Result};
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Asks the user a yes/no question on the terminal
+///
+/// # Arguments
+///
+/// * `question` - The question to ask (without the `[y/N]` suffix)
+///
+/// # Errors
+///
+/// Returns an error if the answer could not be read
+///
+/// # Returns
+///
+/// Whether the user answered yes, or `None` if there is no terminal to ask on (e.g. in a script)
+pub fn confirm(question: &str) -> Result<Option<bool>> {
+    if !io::stdin().is_terminal() {
+        return Ok(None);
+    }
+
+    eprint!("{} [y/N] ", question);
+    io::stderr()
+        .flush()
+        .with_context(|| "Failed to write prompt")?;
+
+    let mut answer = String::new();
+    io::stdin()
+        .lock()
+        .read_line(&mut answer)
+        .with_context(|| "Failed to read answer")?;
+
+    Ok(Some(matches!(
+        answer.trim().to_lowercase().as_str(),
+        "y" | "yes"
+    )))
+}
@@ -0,0 +1,454 @@
+use crate::data::{WindowDataEntry, WindowPosition, WindowSize};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// The values of an entry that describe where and how its window is placed, as compared by `diff_arrangements` with `placement_only`
+const PLACEMENT_FIELDS: [&str; 3] = ["state", "workspace", "monitor"];
+
+/// How an entry differs between two versions of an arrangement
+#[derive(Serialize)]
+#[serde(tag = "change", rename_all = "lowercase")]
+pub enum EntryChange {
+    /// The entry is only in the second version (`index` is its index there)
+    Added {
+        index: usize,
+        application_path: String,
+    },
+    /// The entry is only in the first version (`index` is its index there)
+    Removed {
+        index: usize,
+        application_path: String,
+    },
+    /// The entry is in both versions, with different values (`index` is its index in the second version)
+    Changed {
+        index: usize,
+        application_path: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        moved: Option<GeometryChange<WindowPosition>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        resized: Option<GeometryChange<WindowSize>>,
+        /// Every other value that changed, including `launch`, `reposition` and `state` (`normal`, `minimized` or `maximized`)
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        fields: Vec<FieldChange>,
+    },
+}
+
+/// A change to a window's position or size, with the difference in pixels (`after` - `before`)
+#[derive(Serialize)]
+pub struct GeometryChange<T> {
+    pub before: T,
+    pub after: T,
+    pub delta: T,
+}
+
+/// A value of an entry that differs between two versions of an arrangement (`None` if the field is not set)
+#[derive(Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// Compares the entries of two versions of an arrangement
+///
+/// Each entry in `after` is paired with an entry in `before` for the same application - preferably the same window (by title, then by its position among the application's windows), and otherwise the first one left - so that entries can be reordered without showing up as changes
+///
+/// # Arguments
+///
+/// * `before` - The entries of the first (older) version
+/// * `after` - The entries of the second (newer) version
+/// * `placement_only` - Only compare where windows are and their state, not how they are launched (for comparing with windows that are open, which have nothing but their placement to compare) - the position, size and monitor of entries that are minimized in `after` are not compared either, as they are not read from minimized windows
+///
+/// # Returns
+///
+/// The added, removed and changed entries, in order of their index in `after` (with removed entries last)
+pub fn diff_arrangements(
+    before: &[WindowDataEntry],
+    after: &[WindowDataEntry],
+    placement_only: bool,
+) -> Vec<EntryChange> {
+    let mut paired = vec![false; before.len()];
+    let mut changes = Vec::new();
+
+    for (index, entry) in after.iter().enumerate() {
+        let pair = before
+            .iter()
+            .enumerate()
+            .filter(|(before_index, before_entry)| {
+                !paired[*before_index] && before_entry.application_path == entry.application_path
+            })
+            .min_by_key(|(before_index, before_entry)| {
+                (
+                    before_entry.window_title != entry.window_title,
+                    before_entry.instance != entry.instance,
+                    *before_index,
+                )
+            })
+            .map(|(before_index, _)| before_index);
+
+        let before_entry = match pair {
+            Some(before_index) => {
+                paired[before_index] = true;
+                &before[before_index]
+            }
+            None => {
+                changes.push(EntryChange::Added {
+                    index,
+                    application_path: entry.application_path.clone(),
+                });
+                continue;
+            }
+        };
+
+        let compare_geometry = !(placement_only && entry.minimized);
+        let moved =
+            (compare_geometry && before_entry.position != entry.position).then(|| GeometryChange {
+                before: before_entry.position,
+                after: entry.position,
+                delta: WindowPosition {
+                    top: entry.position.top - before_entry.position.top,
+                    left: entry.position.left - before_entry.position.left,
+                },
+            });
+        let resized =
+            (compare_geometry && before_entry.size != entry.size).then(|| GeometryChange {
+                before: before_entry.size,
+                after: entry.size,
+                delta: WindowSize {
+                    width: entry.size.width - before_entry.size.width,
+                    height: entry.size.height - before_entry.size.height,
+                },
+            });
+        let mut fields = diff_fields(before_entry, entry, placement_only);
+        if !compare_geometry {
+            // The monitor a minimized window is on follows from a position that was not read
+            fields.retain(|field| field.field != "monitor");
+        }
+
+        if moved.is_some() || resized.is_some() || !fields.is_empty() {
+            changes.push(EntryChange::Changed {
+                index,
+                application_path: entry.application_path.clone(),
+                moved,
+                resized,
+                fields,
+            });
+        }
+    }
+
+    for (index, entry) in before.iter().enumerate() {
+        if !paired[index] {
+            changes.push(EntryChange::Removed {
+                index,
+                application_path: entry.application_path.clone(),
+            });
+        }
+    }
+
+    changes
+}
+
+/// Gets the values other than position and size that differ between two entries, by comparing them as they are saved
+fn diff_fields(
+    before: &WindowDataEntry,
+    after: &WindowDataEntry,
+    placement_only: bool,
+) -> Vec<FieldChange> {
+    let before = get_comparable_fields(before, placement_only);
+    let after = get_comparable_fields(after, placement_only);
+
+    let fields: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    fields
+        .into_iter()
+        .filter(|field| before.get(*field) != after.get(*field))
+        .map(|field| FieldChange {
+            field: field.clone(),
+            before: before.get(field).cloned(),
+            after: after.get(field).cloned(),
+        })
+        .collect()
+}
+
+/// Gets the values of an entry as they are compared: without its position and size (which are compared separately), with its minimized and maximized flags combined into `state`, and with only the ID of its monitor (as where it is on the monitor follows from its position)
+fn get_comparable_fields(entry: &WindowDataEntry, placement_only: bool) -> Map<String, Value> {
+    let mut fields = match serde_json::to_value(entry) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    };
+
+    for field in ["position", "size", "minimized", "maximised", "monitor"] {
+        fields.remove(field);
+    }
+    let state = if entry.minimized {
+        "minimized"
+    } else if entry.maximised {
+        "maximized"
+    } else {
+        "normal"
+    };
+    fields.insert(String::from("state"), Value::from(state));
+    if let Some(monitor) = &entry.monitor {
+        fields.insert(String::from("monitor"), Value::from(monitor.id.clone()));
+    }
+
+    if placement_only {
+        fields.retain(|field, _| PLACEMENT_FIELDS.contains(&field.as_str()));
+    }
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{MonitorPlacement, WindowWorkspace};
+
+    const BROWSER: &str = "/usr/bin/browser";
+    const EDITOR: &str = "/usr/bin/editor";
+    const TERMINAL: &str = "/usr/bin/terminal";
+
+    fn entry(application_path: &str, title: Option<&str>, left: i32) -> WindowDataEntry {
+        WindowDataEntry {
+            application_path: application_path.to_string(),
+            application_args: Vec::new(),
+            launch_command: None,
+            working_directory: None,
+            launch: true,
+            reposition: true,
+            position: WindowPosition { top: 0, left },
+            size: WindowSize {
+                width: 800,
+                height: 600,
+            },
+            minimized: false,
+            maximised: false,
+            window_title: title.map(str::to_string),
+            title_pattern: None,
+            window_class: None,
+            instance: None,
+            workspace: None,
+            monitor: None,
+            timeout: None,
+            ready_when: None,
+        }
+    }
+
+    fn with_instance(mut entry: WindowDataEntry, instance: usize) -> WindowDataEntry {
+        entry.instance = Some(instance);
+        entry
+    }
+
+    /// Describes each change in a line, e.g. `~0 /usr/bin/editor moved=+10+0 fields=state`
+    fn describe(changes: &[EntryChange]) -> Vec<String> {
+        changes
+            .iter()
+            .map(|change| match change {
+                EntryChange::Added {
+                    index,
+                    application_path,
+                } => format!("+{} {}", index, application_path),
+                EntryChange::Removed {
+                    index,
+                    application_path,
+                } => format!("-{} {}", index, application_path),
+                EntryChange::Changed {
+                    index,
+                    application_path,
+                    moved,
+                    resized,
+                    fields,
+                } => {
+                    let mut description = format!("~{} {}", index, application_path);
+                    if let Some(moved) = moved {
+                        description +=
+                            &format!(" moved={:+}{:+}", moved.delta.left, moved.delta.top);
+                    }
+                    if let Some(resized) = resized {
+                        description += &format!(
+                            " resized={:+}{:+}",
+                            resized.delta.width, resized.delta.height
+                        );
+                    }
+                    if !fields.is_empty() {
+                        let fields: Vec<&str> =
+                            fields.iter().map(|field| field.field.as_str()).collect();
+                        description += &format!(" fields={}", fields.join(","));
+                    }
+                    description
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn only_pairs_entries_of_same_application() {
+        let before = [entry(EDITOR, None, 0)];
+        let after = [entry(TERMINAL, None, 0)];
+
+        let changes = diff_arrangements(&before, &after, false);
+
+        assert_eq!(
+            describe(&changes),
+            ["+0 /usr/bin/terminal", "-0 /usr/bin/editor"]
+        );
+    }
+
+    #[test]
+    fn pairs_entry_with_same_title() {
+        let before = [
+            entry(EDITOR, Some("notes"), 0),
+            entry(EDITOR, Some("todo"), 100),
+        ];
+        let after = [entry(EDITOR, Some("todo"), 100)];
+
+        let changes = diff_arrangements(&before, &after, false);
+
+        assert_eq!(describe(&changes), ["-0 /usr/bin/editor"]);
+    }
+
+    #[test]
+    fn pairs_entry_with_same_instance_when_titles_match() {
+        let before = [
+            with_instance(entry(EDITOR, None, 0), 0),
+            with_instance(entry(EDITOR, None, 100), 1),
+        ];
+        let after = [with_instance(entry(EDITOR, None, 100), 1)];
+
+        let changes = diff_arrangements(&before, &after, false);
+
+        assert_eq!(describe(&changes), ["-0 /usr/bin/editor"]);
+    }
+
+    #[test]
+    fn title_outweighs_instance_when_pairing() {
+        let before = [
+            with_instance(entry(EDITOR, Some("notes"), 0), 1),
+            with_instance(entry(EDITOR, Some("todo"), 100), 0),
+        ];
+        let after = [with_instance(entry(EDITOR, Some("notes"), 0), 0)];
+
+        let changes = diff_arrangements(&before, &after, false);
+
+        assert_eq!(
+            describe(&changes),
+            ["~0 /usr/bin/editor fields=instance", "-1 /usr/bin/editor"]
+        );
+    }
+
+    #[test]
+    fn pairs_first_unpaired_entry_otherwise() {
+        let before = [
+            entry(EDITOR, Some("notes"), 0),
+            entry(EDITOR, Some("todo"), 100),
+        ];
+        let after = [
+            entry(EDITOR, Some("drafts"), 100),
+            entry(EDITOR, Some("ideas"), 100),
+        ];
+
+        let changes = diff_arrangements(&before, &after, false);
+
+        assert_eq!(
+            describe(&changes),
+            [
+                "~0 /usr/bin/editor moved=+100+0 fields=window_title",
+                "~1 /usr/bin/editor fields=window_title"
+            ]
+        );
+    }
+
+    #[test]
+    fn reordered_entries_are_not_changes() {
+        let before = [
+            entry(BROWSER, Some("Inbox"), 0),
+            entry(EDITOR, Some("notes"), 100),
+            entry(TERMINAL, None, 200),
+        ];
+        let after = [
+            entry(TERMINAL, None, 200),
+            entry(EDITOR, Some("notes"), 100),
+            entry(BROWSER, Some("Inbox"), 0),
+        ];
+
+        let changes = diff_arrangements(&before, &after, false);
+
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn reports_geometry_with_delta() {
+        let before = [entry(EDITOR, None, 100)];
+        let mut after = [entry(EDITOR, None, 60)];
+        after[0].position.top = 20;
+        after[0].size.width = 1024;
+
+        let changes = diff_arrangements(&before, &after, false);
+
+        assert_eq!(
+            describe(&changes),
+            ["~0 /usr/bin/editor moved=-40+20 resized=+224+0"]
+        );
+    }
+
+    #[test]
+    fn folds_minimized_and_maximized_into_state() {
+        let mut before = [entry(EDITOR, None, 0)];
+        before[0].maximised = true;
+        let mut after = [entry(EDITOR, None, 0)];
+        // A window that is minimized is minimized, whether or not it would be maximized when restored
+        after[0].minimized = true;
+        after[0].maximised = true;
+
+        let changes = diff_arrangements(&before, &after, false);
+
+        assert_eq!(describe(&changes), ["~0 /usr/bin/editor fields=state"]);
+        let EntryChange::Changed { fields, .. } = &changes[0] else {
+            unreachable!();
+        };
+        assert_eq!(fields[0].before, Some(Value::from("maximized")));
+        assert_eq!(fields[0].after, Some(Value::from("minimized")));
+    }
+
+    #[test]
+    fn placement_only_ignores_launch_settings() {
+        let before = [entry(EDITOR, Some("notes"), 0)];
+        let mut after = [entry(EDITOR, Some("notes"), 0)];
+        after[0].launch = false;
+        after[0].application_args = vec![String::from("--new-window")];
+        after[0].workspace = Some(WindowWorkspace {
+            name: String::from("2"),
+            floating: false,
+        });
+
+        assert_eq!(
+            describe(&diff_arrangements(&before, &after, false)),
+            ["~0 /usr/bin/editor fields=application_args,launch,workspace"]
+        );
+        assert_eq!(
+            describe(&diff_arrangements(&before, &after, true)),
+            ["~0 /usr/bin/editor fields=workspace"]
+        );
+    }
+
+    #[test]
+    fn placement_only_ignores_geometry_of_minimized_windows() {
+        let before = [entry(EDITOR, None, 0)];
+        let mut after = [entry(EDITOR, None, -32000)];
+        after[0].minimized = true;
+        after[0].monitor = Some(MonitorPlacement {
+            id: String::from("DP-2"),
+            position: after[0].position,
+            size: after[0].size,
+        });
+
+        assert_eq!(
+            describe(&diff_arrangements(&before, &after, true)),
+            ["~0 /usr/bin/editor fields=state"]
+        );
+        assert_eq!(
+            describe(&diff_arrangements(&before, &after, false)),
+            ["~0 /usr/bin/editor moved=-32000+0 fields=monitor,state"]
+        );
+    }
+}
@@ -0,0 +1,22 @@
+use std::{env, path::Path};
+
+/// Checks whether an executable exists, looking it up in the `PATH` environment variable if it is a bare command name
+///
+/// # Arguments
+///
+/// * `program` - The path or command name of the executable
+pub fn executable_exists(program: &str) -> bool {
+    let path = Path::new(program);
+    if path.components().count() > 1 || path.is_absolute() {
+        return path.is_file();
+    }
+
+    let extensions: &[&str] = if cfg!(windows) { &["", ".exe"] } else { &[""] };
+    env::var_os("PATH").is_some_and(|paths| {
+        env::split_paths(&paths).any(|dir_path| {
+            extensions
+                .iter()
+                .any(|extension| dir_path.join(format!("{}{}", program, extension)).is_file())
+        })
+    })
+}
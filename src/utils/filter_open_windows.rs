@@ -0,0 +1,88 @@
+use crate::{
+    config::{WindowFacts, WindowFilters},
+    data::{MonitorData, WindowPosition, WindowSize},
+    platform::WindowSystem,
+    printwarning,
+    utils::{find_monitor, OpenWindow},
+    verbose,
+};
+use anyhow::{Context, Result};
+
+/// Removes the windows that do not pass the given filters
+///
+/// # Arguments
+///
+/// * `window_system` - The window system the windows belong to
+/// * `open_windows` - The windows to filter
+/// * `filters` - The filters to apply
+/// * `monitors` - The connected monitors, for filters that match on monitor
+///
+/// # Errors
+///
+/// Returns an error if one of the filters is invalid. A window whose size or state could not be read (when a filter needs it) is removed, with a warning in verbose mode
+pub fn filter_open_windows(
+    window_system: &dyn WindowSystem,
+    open_windows: &mut Vec<OpenWindow>,
+    filters: &WindowFilters,
+    monitors: &[MonitorData],
+) -> Result<()> {
+    let needs_geometry = filters.needs_geometry();
+    let mut included = Vec::with_capacity(open_windows.len());
+
+    for open_window in open_windows.iter() {
+        let geometry = if needs_geometry {
+            match get_geometry(window_system, open_window) {
+                Ok(geometry) => Some(geometry),
+                Err(error) => {
+                    if verbose() {
+                        printwarning!("{:#}", error);
+                    }
+                    included.push(false);
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+
+        let monitor = geometry.as_ref().and_then(|(position, size, _)| {
+            find_monitor(monitors, position, size).map(|monitor| monitor.id.as_str())
+        });
+        let facts = WindowFacts {
+            module_path: &open_window.module_path,
+            title: &open_window.title,
+            class: open_window.class.as_deref(),
+            monitor,
+            size: geometry
+                .as_ref()
+                .map(|(_, size, _)| *size)
+                .unwrap_or(WindowSize {
+                    width: 0,
+                    height: 0,
+                }),
+            minimized: geometry
+                .as_ref()
+                .map(|(_, _, minimized)| *minimized)
+                .unwrap_or(false),
+        };
+        included.push(filters.is_included(&facts)?);
+    }
+
+    let mut included = included.into_iter();
+    open_windows.retain(|_| included.next().unwrap_or(false));
+
+    Ok(())
+}
+
+fn get_geometry(
+    window_system: &dyn WindowSystem,
+    open_window: &OpenWindow,
+) -> Result<(WindowPosition, WindowSize, bool)> {
+    let (position, size) = window_system
+        .get_rect(open_window.window)
+        .with_context(|| format!("failed to get size of window '{}'", open_window.title))?;
+    let minimized = window_system
+        .is_minimized(open_window.window)
+        .with_context(|| format!("failed to get state of window '{}'", open_window.title))?;
+    Ok((position, size, minimized))
+}
@@ -0,0 +1,182 @@
+use serde::{
+    de::{
+        value::BorrowedStrDeserializer, DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess,
+        Visitor,
+    },
+    forward_to_deserialize_any, Deserializer,
+};
+use serde_json::{map, Error, Value};
+use std::{cell::RefCell, iter::Enumerate, slice};
+
+/// Finds a key in JSON that is not a field of the struct it is in, when the JSON is read as a `T`
+///
+/// Keys are checked against the fields each struct declares, so a misspelled key is found whatever its value is (including `null` or empty, which leave nothing behind when the data is serialized again)
+///
+/// # Returns
+///
+/// The path of the first unknown key (such as `data[0].lanch`), if there is one
+pub fn find_unknown_key<T: DeserializeOwned>(value: &Value) -> Option<String> {
+    let unknown_key = RefCell::new(None);
+    // Any error is one that reading the JSON as a `T` gives anyway, so only the keys are of interest here
+    let _ = T::deserialize(KeyChecker {
+        value,
+        path: String::new(),
+        unknown_key: &unknown_key,
+    });
+    unknown_key.into_inner()
+}
+
+/// Reads a JSON value as serde asks for it, noting the first key that is not a field of the struct being read
+struct KeyChecker<'de> {
+    value: &'de Value,
+    /// Where the value is, such as `data[0].position`
+    path: String,
+    unknown_key: &'de RefCell<Option<String>>,
+}
+
+impl<'de> KeyChecker<'de> {
+    fn visit<V: Visitor<'de>>(
+        self,
+        fields: Option<&'static [&'static str]>,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Object(values) => visitor.visit_map(MapChecker {
+                entries: values.iter(),
+                value: None,
+                path: self.path,
+                key_path: String::new(),
+                fields,
+                unknown_key: self.unknown_key,
+            }),
+            Value::Array(values) => visitor.visit_seq(SeqChecker {
+                elements: values.iter().enumerate(),
+                path: self.path,
+                unknown_key: self.unknown_key,
+            }),
+            value => value.deserialize_any(visitor),
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for KeyChecker<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.visit(None, visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.visit(Some(fields), visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.value.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct seq tuple tuple_struct map identifier ignored_any
+    }
+}
+
+struct MapChecker<'de> {
+    entries: map::Iter<'de>,
+    /// The value of the key that was read last
+    value: Option<&'de Value>,
+    path: String,
+    key_path: String,
+    /// The fields of the struct being read, or `None` if it is a map (which can have any keys)
+    fields: Option<&'static [&'static str]>,
+    unknown_key: &'de RefCell<Option<String>>,
+}
+
+impl<'de> MapAccess<'de> for MapChecker<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        self.key_path = if self.path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", self.path, key)
+        };
+        self.value = Some(value);
+
+        if self
+            .fields
+            .is_some_and(|fields| !fields.contains(&key.as_str()))
+        {
+            self.unknown_key
+                .borrow_mut()
+                .get_or_insert_with(|| self.key_path.clone());
+        }
+
+        seed.deserialize(BorrowedStrDeserializer::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self.value.take().unwrap_or(&Value::Null);
+        seed.deserialize(KeyChecker {
+            value,
+            path: self.key_path.clone(),
+            unknown_key: self.unknown_key,
+        })
+    }
+}
+
+struct SeqChecker<'de> {
+    elements: Enumerate<slice::Iter<'de, Value>>,
+    path: String,
+    unknown_key: &'de RefCell<Option<String>>,
+}
+
+impl<'de> SeqAccess<'de> for SeqChecker<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        let Some((index, value)) = self.elements.next() else {
+            return Ok(None);
+        };
+        seed.deserialize(KeyChecker {
+            value,
+            path: format!("{}[{}]", self.path, index),
+            unknown_key: self.unknown_key,
+        })
+        .map(Some)
+    }
+}
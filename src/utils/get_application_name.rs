@@ -0,0 +1,20 @@
+/// Gets the file name of an application from its path (without `.exe`)
+///
+/// # Arguments
+///
+/// * `application_path` - The full path of the application's executable
+pub fn get_application_name(application_path: &str) -> String {
+    let file_name = application_path
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(application_path);
+    match file_name.len().checked_sub(4) {
+        Some(stem_length)
+            if file_name.is_char_boundary(stem_length)
+                && file_name[stem_length..].eq_ignore_ascii_case(".exe") =>
+        {
+            file_name[..stem_length].to_string()
+        }
+        _ => file_name.to_string(),
+    }
+}
@@ -0,0 +1,26 @@
+use crate::{data::MonitorData, platform::WindowSystem, printwarning, verbose};
+use anyhow::Context;
+
+/// Gets the monitors that are currently connected
+///
+/// # Arguments
+///
+/// * `window_system` - The window system to query
+///
+/// # Errors
+///
+/// The function does not error, but will print a warning (in verbose mode) and return no monitors if they could not be obtained, in which case windows are placed at their absolute saved positions
+pub fn get_monitors(window_system: &dyn WindowSystem) -> Vec<MonitorData> {
+    match window_system
+        .get_monitors()
+        .with_context(|| "failed to get monitors")
+    {
+        Ok(monitors) => monitors,
+        Err(error) => {
+            if verbose() {
+                printwarning!("{:#}", error);
+            }
+            Vec::new()
+        }
+    }
+}
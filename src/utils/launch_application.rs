@@ -1,5 +1,9 @@
+use crate::{printwarning, verbose};
 use anyhow::{Context, Result};
-use std::process::{Command, Stdio};
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
 
 /// Runs a command to launch an application with arguments
 ///
@@ -7,13 +11,29 @@ use std::process::{Command, Stdio};
 ///
 /// * `application_path` - A path to the application's executable
 /// * `application_args` - A vector of arguments to be passed to the application
+/// * `working_directory` - The directory to launch the application in (ignored if it no longer exists)
 ///
 /// # Errors
 ///
 /// Returns an error if the command process could not be spawned successfully
-pub fn launch_application(application_path: &String, application_args: &Vec<String>) -> Result<()> {
+pub fn launch_application(
+    application_path: &str,
+    application_args: &[String],
+    working_directory: Option<&str>,
+) -> Result<()> {
     let mut launch_command = Command::new(application_path);
     launch_command.args(application_args);
+    if let Some(directory) = working_directory {
+        if Path::new(directory).is_dir() {
+            launch_command.current_dir(directory);
+        } else if verbose() {
+            printwarning!(
+                "working directory '{}' no longer exists - launching '{}' without it",
+                directory,
+                application_path
+            );
+        }
+    }
     launch_command.stderr(Stdio::null());
     launch_command.stdin(Stdio::null());
     launch_command.stdout(Stdio::null());
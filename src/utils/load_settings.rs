@@ -0,0 +1,57 @@
+use crate::{
+    config::{ConfigData, OtherWindows, WindowFilter},
+    data::ArrangementSettings,
+    utils::WaitPolicy,
+};
+use std::time::Duration;
+
+/// How an arrangement is loaded, from the configuration, overridden by the arrangement's settings and then by any command-line flags
+pub struct LoadSettings {
+    pub wait_policy: WaitPolicy,
+    pub other_windows: OtherWindows,
+    /// How many launched applications are waited for at once (0 = no limit)
+    pub launch_concurrency: usize,
+    pub verify_placement: bool,
+    /// How far (in pixels) a window may be from where it was placed before it is moved back
+    pub verify_tolerance: i32,
+    /// How many times a window that has drifted is moved back into place
+    pub verify_retries: usize,
+    /// How long windows are given to settle before their placement is checked
+    pub verify_delay: Duration,
+    /// Windows that a load must leave alone (which are neither matched to entries, nor closed or minimized)
+    pub exclude_windows: Vec<WindowFilter>,
+}
+
+impl LoadSettings {
+    /// Gets the settings for loading an arrangement
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration to load the arrangement with
+    /// * `settings` - The settings of the arrangement being loaded
+    /// * `wait_policy` - How windows are waited for (already resolved, see `WaitPolicy::from_config`)
+    /// * `other_windows_flag` - Overrides the `other_windows` configuration value
+    /// * `launch_concurrency_flag` - Overrides the `launch_concurrency` configuration value
+    pub fn from_config(
+        config: &ConfigData,
+        settings: &ArrangementSettings,
+        wait_policy: WaitPolicy,
+        other_windows_flag: Option<OtherWindows>,
+        launch_concurrency_flag: Option<usize>,
+    ) -> Self {
+        LoadSettings {
+            wait_policy,
+            other_windows: other_windows_flag
+                .or(settings.other_windows)
+                .unwrap_or(config.other_windows),
+            launch_concurrency: launch_concurrency_flag
+                .or(settings.launch_concurrency)
+                .unwrap_or(config.launch_concurrency),
+            verify_placement: settings.verify_placement.unwrap_or(config.verify_placement),
+            verify_tolerance: config.verify_tolerance as i32,
+            verify_retries: config.verify_retries,
+            verify_delay: Duration::from_millis(config.verify_delay as u64),
+            exclude_windows: config.exclude_windows.clone(),
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{
+    data::WindowDataEntry,
+    platform::{WindowId, WindowSystem},
+    utils::get_module_paths_from_windows,
+    verbose,
+};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+
+/// What is known about an open window when matching it to arrangement entries
+pub struct OpenWindow {
+    pub window: WindowId,
+    pub module_path: String,
+    pub title: String,
+    pub class: Option<String>,
+    /// The position of this window among the open windows of the same application, starting at 0
+    pub instance: usize,
+}
+
+/// Given a vector of windows, gets the details needed to match them to arrangement entries
+///
+/// # Arguments
+///
+/// * `window_system` - The window system the windows belong to
+/// * `windows` - A vector of windows
+///
+/// # Errors
+///
+/// The function does not error, but windows whose module path could not be obtained are left out, and a title or class that could not be obtained is left empty (with a warning in verbose mode)
+pub fn get_open_window_details(
+    window_system: &dyn WindowSystem,
+    windows: &[WindowId],
+) -> Vec<OpenWindow> {
+    let module_paths = get_module_paths_from_windows(window_system, windows);
+    let mut instance_counts: HashMap<String, usize> = HashMap::new();
+    let mut open_windows = Vec::new();
+
+    for (window, module_path) in windows.iter().zip(module_paths) {
+        let module_path = match module_path {
+            Some(data) => data,
+            None => continue,
+        };
+
+        let title = window_system
+            .get_title(*window)
+            .with_context(|| "failed to get window title")
+            .unwrap_or_else(|error| {
+                if verbose() {
+                    printwarning!("{:#}", error);
+                }
+                String::new()
+            });
+        let class = window_system
+            .get_class(*window)
+            .with_context(|| "failed to get window class")
+            .unwrap_or_else(|error| {
+                if verbose() {
+                    printwarning!("{:#}", error);
+                }
+                None
+            });
+
+        let instance_count = instance_counts.entry(module_path.clone()).or_default();
+        open_windows.push(OpenWindow {
+            window: *window,
+            module_path,
+            title,
+            class,
+            instance: *instance_count,
+        });
+        *instance_count += 1;
+    }
+
+    open_windows
+}
+
+/// Assigns open windows to arrangement entries, so that each entry gets at most one window and each window is used at most once
+///
+/// A window can only be assigned to an entry with the same application path (and whose `title_pattern`, if any, matches the window's title). Among those, pairs are chosen best-first, preferring an identical title, then the same window class, then the same instance ordinal, then the order of the entries
+///
+/// # Arguments
+///
+/// * `entries` - The arrangement entries
+/// * `pending` - Which entries still need a window (same length as `entries`)
+/// * `open_windows` - The windows available for assignment
+///
+/// # Errors
+///
+/// Returns an error if an entry's `title_pattern` is not a valid regular expression
+pub fn match_windows(
+    entries: &[WindowDataEntry],
+    pending: &[bool],
+    open_windows: &[OpenWindow],
+) -> Result<Vec<(usize, usize)>> {
+    let mut candidates: Vec<(u32, usize, usize)> = Vec::new();
+
+    for (entry_index, entry) in entries.iter().enumerate() {
+        if !pending[entry_index] {
+            continue;
+        }
+
+        let title_pattern = match &entry.title_pattern {
+            Some(pattern) => Some(Regex::new(pattern).with_context(|| {
+                format!(
+                    "Invalid title pattern for '{}' (entry {})",
+                    entry.application_path, entry_index
+                )
+            })?),
+            None => None,
+        };
+
+        for (window_index, open_window) in open_windows.iter().enumerate() {
+            if open_window.module_path != entry.application_path {
+                continue;
+            }
+            if let Some(pattern) = &title_pattern {
+                if !pattern.is_match(&open_window.title) {
+                    continue;
+                }
+            }
+
+            let mut score = 0;
+            if entry.window_title.as_ref() == Some(&open_window.title) {
+                score += 4;
+            }
+            if entry.window_class.is_some() && entry.window_class == open_window.class {
+                score += 2;
+            }
+            if entry.instance == Some(open_window.instance) {
+                score += 1;
+            }
+            candidates.push((score, entry_index, window_index));
+        }
+    }
+
+    // Highest score first; ties go to the earlier entry, then the earlier window
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+    let mut entry_taken = vec![false; entries.len()];
+    let mut window_taken = vec![false; open_windows.len()];
+    let mut assignments = Vec::new();
+    for (_, entry_index, window_index) in candidates {
+        if entry_taken[entry_index] || window_taken[window_index] {
+            continue;
+        }
+        entry_taken[entry_index] = true;
+        window_taken[window_index] = true;
+        assignments.push((entry_index, window_index));
+    }
+
+    Ok(assignments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{WindowPosition, WindowSize};
+
+    const EDITOR: &str = "/usr/bin/editor";
+    const TERMINAL: &str = "/usr/bin/terminal";
+
+    fn entry(application_path: &str) -> WindowDataEntry {
+        WindowDataEntry {
+            application_path: application_path.to_string(),
+            application_args: Vec::new(),
+            launch_command: None,
+            working_directory: None,
+            launch: true,
+            reposition: true,
+            position: WindowPosition { top: 0, left: 0 },
+            size: WindowSize {
+                width: 800,
+                height: 600,
+            },
+            minimized: false,
+            maximised: false,
+            window_title: None,
+            title_pattern: None,
+            window_class: None,
+            instance: None,
+            workspace: None,
+            monitor: None,
+            timeout: None,
+            ready_when: None,
+        }
+    }
+
+    /// Gets open windows from their module paths and titles, numbering instances as `get_open_window_details` does
+    fn open_windows(windows: &[(&str, &str)]) -> Vec<OpenWindow> {
+        let mut instance_counts: HashMap<&str, usize> = HashMap::new();
+        windows
+            .iter()
+            .enumerate()
+            .map(|(index, (module_path, title))| {
+                let instance_count = instance_counts.entry(module_path).or_default();
+                let open_window = OpenWindow {
+                    window: WindowId(index as u64 + 1),
+                    module_path: module_path.to_string(),
+                    title: title.to_string(),
+                    class: None,
+                    instance: *instance_count,
+                };
+                *instance_count += 1;
+                open_window
+            })
+            .collect()
+    }
+
+    fn match_all(entries: &[WindowDataEntry], open_windows: &[OpenWindow]) -> Vec<(usize, usize)> {
+        let mut assignments =
+            match_windows(entries, &vec![true; entries.len()], open_windows).unwrap();
+        assignments.sort();
+        assignments
+    }
+
+    #[test]
+    fn title_outweighs_class_and_class_outweighs_instance() {
+        let mut by_title = entry(EDITOR);
+        by_title.window_title = Some(String::from("notes.txt"));
+        let mut by_class = entry(EDITOR);
+        by_class.window_class = Some(String::from("Notes"));
+        let mut by_instance = entry(EDITOR);
+        by_instance.instance = Some(0);
+        let mut windows = open_windows(&[
+            (EDITOR, "notes.txt"),
+            (EDITOR, "todo.txt"),
+            (EDITOR, "log.txt"),
+        ]);
+        windows[0].class = Some(String::from("Notes"));
+        windows[1].class = Some(String::from("Notes"));
+
+        // The instance entry would take window 0 if it were matched first, and the class entry would take window 0 or 1
+        let assignments = match_all(&[by_instance, by_class, by_title], &windows);
+
+        assert_eq!(assignments, vec![(0, 2), (1, 1), (2, 0)]);
+    }
+
+    #[test]
+    fn title_beats_instance_ordinal() {
+        // The window that was first when the arrangement was saved is now second
+        let mut notes = entry(EDITOR);
+        notes.window_title = Some(String::from("notes.txt"));
+        notes.instance = Some(0);
+        let mut todo = entry(EDITOR);
+        todo.window_title = Some(String::from("todo.txt"));
+        todo.instance = Some(1);
+        let windows = open_windows(&[(EDITOR, "todo.txt"), (EDITOR, "notes.txt")]);
+
+        let assignments = match_all(&[notes, todo], &windows);
+
+        assert_eq!(assignments, vec![(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn title_pattern_rules_out_other_windows() {
+        let mut editor = entry(EDITOR);
+        editor.title_pattern = Some(String::from(r"\.rs$"));
+        let windows = open_windows(&[(EDITOR, "notes.txt"), (EDITOR, "main.rs")]);
+
+        assert_eq!(match_all(&[editor], &windows), vec![(0, 1)]);
+
+        let mut editor = entry(EDITOR);
+        editor.title_pattern = Some(String::from(r"\.rs$"));
+        let windows = open_windows(&[(EDITOR, "notes.txt")]);
+
+        assert!(match_all(&[editor], &windows).is_empty());
+    }
+
+    #[test]
+    fn invalid_title_pattern_is_an_error() {
+        let mut editor = entry(EDITOR);
+        editor.title_pattern = Some(String::from("("));
+        let windows = open_windows(&[(EDITOR, "notes.txt")]);
+
+        assert!(match_windows(&[editor], &[true], &windows).is_err());
+    }
+
+    #[test]
+    fn each_entry_gets_at_most_one_window() {
+        let windows = open_windows(&[(EDITOR, "notes.txt"), (EDITOR, "todo.txt")]);
+
+        assert_eq!(match_all(&[entry(EDITOR)], &windows), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn windows_of_same_application_go_to_distinct_entries() {
+        let windows = open_windows(&[
+            (EDITOR, "notes.txt"),
+            (TERMINAL, "bash"),
+            (EDITOR, "todo.txt"),
+        ]);
+
+        let assignments = match_all(&[entry(EDITOR), entry(EDITOR), entry(EDITOR)], &windows);
+
+        // The third entry is left without a window, rather than sharing one
+        assert_eq!(assignments, vec![(0, 0), (1, 2)]);
+    }
+
+    #[test]
+    fn ties_go_to_earlier_entry_then_earlier_window() {
+        let mut first = entry(EDITOR);
+        first.window_title = Some(String::from("notes.txt"));
+        let mut second = entry(EDITOR);
+        second.window_title = Some(String::from("notes.txt"));
+        let windows = open_windows(&[(EDITOR, "notes.txt"), (EDITOR, "notes.txt")]);
+
+        assert_eq!(match_all(&[first, second], &windows), vec![(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn skips_entries_that_are_not_pending() {
+        let windows = open_windows(&[(EDITOR, "notes.txt")]);
+
+        let assignments =
+            match_windows(&[entry(EDITOR), entry(EDITOR)], &[false, true], &windows).unwrap();
+
+        assert_eq!(assignments, vec![(1, 0)]);
+    }
+}
@@ -1,19 +1,51 @@
+mod apply_window_rules;
+mod capture_arrangement;
+mod check_overwrite;
+mod check_readiness;
+mod confirm;
+mod diff_arrangements;
+mod executable_exists;
+mod filter_open_windows;
+mod find_unknown_key;
+mod get_application_name;
 mod get_module_paths_from_windows;
-mod get_open_windows;
+mod get_monitors;
 mod get_root_dir_path;
 mod launch_application;
+mod load_settings;
 #[macro_use]
 mod logging;
-mod reposition_and_resize_window;
+mod match_windows;
+mod monitors;
+mod print_table;
+mod record_revision;
 mod resource_exists;
 mod validate_name;
+mod verify_placements;
+mod wait_policy;
+mod write_file_atomically;
 
-pub use get_module_paths_from_windows::{
-    get_module_path_from_window, get_module_paths_from_windows,
-};
-pub use get_open_windows::get_open_windows;
+pub use apply_window_rules::apply_window_rules;
+pub use capture_arrangement::capture_arrangement;
+pub use check_overwrite::check_overwrite;
+pub use check_readiness::{check_readiness, GeometryHistory};
+pub use confirm::confirm;
+pub use diff_arrangements::{diff_arrangements, EntryChange};
+pub use executable_exists::executable_exists;
+pub use filter_open_windows::filter_open_windows;
+pub use find_unknown_key::find_unknown_key;
+pub use get_application_name::get_application_name;
+pub use get_module_paths_from_windows::get_module_paths_from_windows;
+pub use get_monitors::get_monitors;
 pub use get_root_dir_path::get_root_dir_path;
 pub use launch_application::launch_application;
-pub use reposition_and_resize_window::reposition_and_resize_window;
+pub use load_settings::LoadSettings;
+pub use match_windows::{get_open_window_details, match_windows, OpenWindow};
+pub use monitors::{find_monitor, get_monitor_placement, remap_to_monitor};
+pub use print_table::print_table;
+pub use record_revision::record_revision;
 pub use resource_exists::{resource_exists, ResourceType};
 pub use validate_name::validate_name;
+pub use verify_placements::{verify_placements, Placement};
+pub use wait_policy::WaitPolicy;
+pub use write_file_atomically::write_file_atomically;
@@ -0,0 +1,281 @@
+use crate::data::{MonitorData, MonitorPlacement, WindowDataEntry, WindowPosition, WindowSize};
+
+/// Finds the monitor that a window is on (the one it overlaps the most), falling back to the primary monitor if it is off-screen
+///
+/// # Arguments
+///
+/// * `monitors` - The monitors to search
+/// * `position` - The position of the window
+/// * `size` - The size of the window
+pub fn find_monitor<'a>(
+    monitors: &'a [MonitorData],
+    position: &WindowPosition,
+    size: &WindowSize,
+) -> Option<&'a MonitorData> {
+    let overlaps = monitors.iter().map(|monitor| {
+        let overlap_width = (position.left + size.width)
+            .min(monitor.bounds.left + monitor.bounds.width)
+            - position.left.max(monitor.bounds.left);
+        let overlap_height = (position.top + size.height)
+            .min(monitor.bounds.top + monitor.bounds.height)
+            - position.top.max(monitor.bounds.top);
+        (
+            monitor,
+            overlap_width.max(0) as i64 * overlap_height.max(0) as i64,
+        )
+    });
+
+    match overlaps
+        .filter(|(_, area)| *area > 0)
+        .max_by_key(|(_, area)| *area)
+    {
+        Some((monitor, _)) => Some(monitor),
+        None => get_primary_monitor(monitors),
+    }
+}
+
+/// Describes where a window is relative to the work area of a monitor
+///
+/// # Arguments
+///
+/// * `monitor` - The monitor the window is on
+/// * `position` - The position of the window
+/// * `size` - The size of the window
+pub fn get_monitor_placement(
+    monitor: &MonitorData,
+    position: &WindowPosition,
+    size: &WindowSize,
+) -> MonitorPlacement {
+    MonitorPlacement {
+        id: monitor.id.clone(),
+        position: WindowPosition {
+            top: position.top - monitor.work_area.top,
+            left: position.left - monitor.work_area.left,
+        },
+        size: *size,
+    }
+}
+
+/// Gets where an entry's window should be placed on the monitors that are currently connected
+///
+/// If the entry's monitor is still connected with the same work area, the saved position and size are used as they are. Otherwise the window is scaled proportionally onto the closest matching monitor (the same monitor, then one with the same bounds, then one with the same size, then the primary monitor) and kept within its work area
+///
+/// # Arguments
+///
+/// * `entry` - The arrangement entry
+/// * `saved_monitors` - The monitors that were connected when the arrangement was saved
+/// * `current_monitors` - The monitors that are currently connected
+pub fn remap_to_monitor(
+    entry: &WindowDataEntry,
+    saved_monitors: &[MonitorData],
+    current_monitors: &[MonitorData],
+) -> (WindowPosition, WindowSize) {
+    let placement = match &entry.monitor {
+        Some(data) => data,
+        None => return (entry.position, entry.size),
+    };
+    let saved_monitor = saved_monitors
+        .iter()
+        .find(|monitor| monitor.id == placement.id);
+
+    let target_monitor = current_monitors
+        .iter()
+        .find(|monitor| monitor.id == placement.id)
+        .or_else(|| {
+            let saved_monitor = saved_monitor?;
+            current_monitors
+                .iter()
+                .find(|monitor| monitor.bounds == saved_monitor.bounds)
+                .or_else(|| {
+                    current_monitors.iter().find(|monitor| {
+                        monitor.bounds.width == saved_monitor.bounds.width
+                            && monitor.bounds.height == saved_monitor.bounds.height
+                    })
+                })
+        })
+        .or_else(|| get_primary_monitor(current_monitors));
+    let target_monitor = match target_monitor {
+        Some(data) => data,
+        None => return (entry.position, entry.size),
+    };
+
+    if let Some(saved_monitor) = saved_monitor {
+        if saved_monitor.work_area == target_monitor.work_area {
+            return (entry.position, entry.size);
+        }
+    }
+
+    let target = &target_monitor.work_area;
+    let (scale_x, scale_y) = match saved_monitor {
+        Some(saved_monitor)
+            if saved_monitor.work_area.width > 0 && saved_monitor.work_area.height > 0 =>
+        {
+            (
+                target.width as f64 / saved_monitor.work_area.width as f64,
+                target.height as f64 / saved_monitor.work_area.height as f64,
+            )
+        }
+        _ => (1.0, 1.0),
+    };
+
+    let width = ((placement.size.width as f64 * scale_x).round() as i32).min(target.width);
+    let height = ((placement.size.height as f64 * scale_y).round() as i32).min(target.height);
+    let left = clamp_to_range(
+        target.left + (placement.position.left as f64 * scale_x).round() as i32,
+        target.left,
+        target.left + target.width - width,
+    );
+    let top = clamp_to_range(
+        target.top + (placement.position.top as f64 * scale_y).round() as i32,
+        target.top,
+        target.top + target.height - height,
+    );
+
+    (WindowPosition { top, left }, WindowSize { width, height })
+}
+
+fn get_primary_monitor(monitors: &[MonitorData]) -> Option<&MonitorData> {
+    monitors
+        .iter()
+        .find(|monitor| monitor.primary)
+        .or_else(|| monitors.first())
+}
+
+fn clamp_to_range(value: i32, min: i32, max: i32) -> i32 {
+    value.min(max).max(min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::ScreenRect;
+
+    fn monitor(id: &str, left: i32, top: i32, width: i32, height: i32) -> MonitorData {
+        let bounds = ScreenRect {
+            left,
+            top,
+            width,
+            height,
+        };
+        MonitorData {
+            id: id.to_string(),
+            bounds,
+            work_area: bounds,
+            scale: 1.0,
+            primary: left == 0 && top == 0,
+        }
+    }
+
+    /// Gets an entry placed on monitor `DP-1`, at `left`/`top` within its work area
+    fn entry(left: i32, top: i32, width: i32, height: i32) -> WindowDataEntry {
+        let position = WindowPosition { top, left };
+        let size = WindowSize { width, height };
+        WindowDataEntry {
+            application_path: String::from("/usr/bin/editor"),
+            application_args: Vec::new(),
+            launch_command: None,
+            working_directory: None,
+            launch: true,
+            reposition: true,
+            position,
+            size,
+            minimized: false,
+            maximised: false,
+            window_title: None,
+            title_pattern: None,
+            window_class: None,
+            instance: None,
+            workspace: None,
+            monitor: Some(MonitorPlacement {
+                id: String::from("DP-1"),
+                position,
+                size,
+            }),
+            timeout: None,
+            ready_when: None,
+        }
+    }
+
+    fn rect(left: i32, top: i32, width: i32, height: i32) -> (WindowPosition, WindowSize) {
+        (WindowPosition { top, left }, WindowSize { width, height })
+    }
+
+    #[test]
+    fn keeps_position_on_unchanged_monitor() {
+        let monitors = [monitor("DP-1", 0, 0, 1920, 1080)];
+
+        let remapped = remap_to_monitor(&entry(100, 50, 800, 600), &monitors, &monitors);
+
+        assert_eq!(remapped, rect(100, 50, 800, 600));
+    }
+
+    #[test]
+    fn keeps_position_without_monitor_placement() {
+        let mut entry = entry(100, 50, 800, 600);
+        entry.monitor = None;
+
+        let remapped = remap_to_monitor(
+            &entry,
+            &[monitor("DP-1", 0, 0, 1920, 1080)],
+            &[monitor("DP-2", 0, 0, 1280, 720)],
+        );
+
+        assert_eq!(remapped, rect(100, 50, 800, 600));
+    }
+
+    #[test]
+    fn scales_down_onto_smaller_monitor() {
+        let remapped = remap_to_monitor(
+            &entry(300, 150, 900, 600),
+            &[monitor("DP-1", 0, 0, 1920, 1080)],
+            &[monitor("DP-2", 0, 0, 1280, 720)],
+        );
+
+        assert_eq!(remapped, rect(200, 100, 600, 400));
+    }
+
+    #[test]
+    fn scales_up_onto_larger_monitor() {
+        let remapped = remap_to_monitor(
+            &entry(100, 50, 800, 600),
+            &[monitor("DP-1", 0, 0, 1920, 1080)],
+            &[monitor("DP-2", 0, 0, 3840, 2160)],
+        );
+
+        assert_eq!(remapped, rect(200, 100, 1600, 1200));
+    }
+
+    #[test]
+    fn clamps_into_work_area_of_offset_monitor() {
+        // The saved monitor is gone, and the closest match is a monitor of the same size to the right of the primary one, with a panel along its top
+        let mut target = monitor("HDMI-1", 1280, 0, 2560, 1440);
+        target.work_area = ScreenRect {
+            left: 1280,
+            top: 40,
+            width: 2560,
+            height: 1400,
+        };
+        let current_monitors = [monitor("eDP-1", 0, 0, 1280, 720), target];
+        let saved_monitors = [monitor("DP-1", 0, 0, 2560, 1440)];
+
+        // Moved onto the shorter work area, the window would hang off its bottom-right corner
+        let remapped = remap_to_monitor(
+            &entry(2200, 1200, 600, 400),
+            &saved_monitors,
+            &current_monitors,
+        );
+
+        assert_eq!(remapped, rect(3240, 1051, 600, 389));
+    }
+
+    #[test]
+    fn shrinks_window_larger_than_work_area() {
+        let remapped = remap_to_monitor(
+            &entry(0, 0, 1920, 1080),
+            &[monitor("DP-1", 0, 0, 1920, 1080)],
+            &[monitor("DP-2", 0, 0, 1280, 720)],
+        );
+
+        assert_eq!(remapped, rect(0, 0, 1280, 720));
+    }
+}
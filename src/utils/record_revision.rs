@@ -0,0 +1,114 @@
+use crate::{
+    data::{
+        get_latest_revision_number, prune_revisions, read_revision, write_revision, Revision,
+        WindowData,
+    },
+    utils::{diff_arrangements, EntryChange},
+    CONFIG,
+};
+use anyhow::Result;
+use chrono::{TimeDelta, Utc};
+
+/// Adds an arrangement that has just been written to its revision history, and removes the revisions that are past the configured limits
+///
+/// Failing to record a revision does not undo the write, so errors are only warned about
+///
+/// # Arguments
+///
+/// * `window_data` - The arrangement, as it was written
+/// * `source` - What wrote the arrangement (e.g. `save` or `migrate`)
+pub fn record_revision(window_data: WindowData, source: &str) {
+    if CONFIG.history_size == 0 {
+        return;
+    }
+
+    if let Err(error) = try_record_revision(window_data, source) {
+        printwarning!("{:#}", error.context("failed to record revision"));
+    }
+}
+
+fn try_record_revision(window_data: WindowData, source: &str) -> Result<()> {
+    let name = window_data.name.clone();
+    let latest_number = get_latest_revision_number(&name)?;
+    let previous = match latest_number {
+        Some(number) => read_revision(&name, number).ok(),
+        None => None,
+    };
+
+    write_revision(&Revision {
+        number: latest_number.unwrap_or_default() + 1,
+        saved_at: Utc::now(),
+        source: source.to_string(),
+        summary: summarise_changes(
+            previous.as_ref().map(|revision| &revision.window_data),
+            &window_data,
+        ),
+        window_data,
+    })?;
+
+    let oldest = CONFIG
+        .history_max_age
+        .and_then(|days| TimeDelta::try_days(days as i64))
+        .and_then(|max_age| Utc::now().checked_sub_signed(max_age));
+    prune_revisions(&name, CONFIG.history_size, oldest)
+}
+
+/// Describes how an arrangement changed from its previous revision (e.g. `1 entry added, 2 entries changed`)
+fn summarise_changes(previous: Option<&WindowData>, current: &WindowData) -> String {
+    let previous = match previous {
+        Some(data) => data,
+        None => return format!("{} entries", current.data.len()),
+    };
+
+    let changes = diff_arrangements(&previous.data, &current.data, false);
+    let count =
+        |matches: fn(&EntryChange) -> bool| changes.iter().filter(|change| matches(change)).count();
+    let mut parts: Vec<String> = [
+        (
+            count(|change| matches!(change, EntryChange::Added { .. })),
+            "added",
+        ),
+        (
+            count(|change| matches!(change, EntryChange::Removed { .. })),
+            "removed",
+        ),
+        (
+            count(|change| matches!(change, EntryChange::Changed { .. })),
+            "changed",
+        ),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, action)| {
+        format!(
+            "{} {} {}",
+            count,
+            if count == 1 { "entry" } else { "entries" },
+            action
+        )
+    })
+    .collect();
+
+    if serde_json::to_value(&previous.settings).ok() != serde_json::to_value(&current.settings).ok()
+    {
+        parts.push(String::from("settings changed"));
+    }
+    if previous.description != current.description {
+        parts.push(String::from("description changed"));
+    }
+    if previous.tags != current.tags {
+        parts.push(String::from("tags changed"));
+    }
+
+    if parts.is_empty()
+        && serde_json::to_value(&previous.data).ok() != serde_json::to_value(&current.data).ok()
+    {
+        parts.push(String::from("entries reordered"));
+    }
+
+    if parts.is_empty() {
+        String::from("no changes")
+    } else {
+        parts.join(", ")
+    }
+}
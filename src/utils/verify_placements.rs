@@ -0,0 +1,86 @@
+use crate::{
+    data::{PlacementDrift, WindowPosition, WindowSize},
+    platform::{WindowId, WindowSystem},
+};
+use anyhow::Result;
+use std::time::Duration;
+
+/// Where a window was placed
+pub struct Placement {
+    pub window: WindowId,
+    pub position: WindowPosition,
+    pub size: WindowSize,
+}
+
+/// Re-reads the geometry of placed windows after giving them time to settle, moving any that have drifted back into place
+///
+/// # Arguments
+///
+/// * `window_system` - The window system the windows belong to
+/// * `placements` - Where each window was placed
+/// * `tolerance` - How far (in pixels) a window may be from where it was placed
+/// * `retries` - How many times a drifted window is moved back into place
+/// * `delay` - How long windows are given to settle after being placed
+///
+/// # Returns
+///
+/// The final drift of each window (in the same order as `placements`), or the error that stopped it from being read or moved
+pub fn verify_placements(
+    window_system: &dyn WindowSystem,
+    placements: &[Placement],
+    tolerance: i32,
+    retries: usize,
+    delay: Duration,
+) -> Vec<Result<PlacementDrift>> {
+    let mut results: Vec<Result<PlacementDrift>> = placements
+        .iter()
+        .map(|_| Ok(PlacementDrift::default()))
+        .collect();
+    let mut pending = vec![true; placements.len()];
+
+    for retry in 0..=retries {
+        window_system.sleep(delay);
+
+        for (index, placement) in placements.iter().enumerate() {
+            if !pending[index] {
+                continue;
+            }
+
+            let drift = get_drift(window_system, placement);
+            let drifted = match &drift {
+                Ok(drift) => !drift.position_within(tolerance) || !drift.size_within(tolerance),
+                Err(_) => false,
+            };
+            pending[index] = drifted;
+            results[index] = drift;
+
+            if drifted && retry < retries {
+                if let Err(error) = window_system.reposition_and_resize(
+                    placement.window,
+                    &placement.position,
+                    &placement.size,
+                ) {
+                    pending[index] = false;
+                    results[index] = Err(error);
+                }
+            }
+        }
+
+        if !pending.contains(&true) {
+            break;
+        }
+    }
+
+    results
+}
+
+fn get_drift(window_system: &dyn WindowSystem, placement: &Placement) -> Result<PlacementDrift> {
+    let (position, size) = window_system.get_rect(placement.window)?;
+
+    Ok(PlacementDrift {
+        left: position.left - placement.position.left,
+        top: position.top - placement.position.top,
+        width: size.width - placement.size.width,
+        height: size.height - placement.size.height,
+    })
+}
@@ -0,0 +1,116 @@
+use crate::{
+    config::{Backoff, ConfigData},
+    data::ArrangementSettings,
+};
+use std::time::Duration;
+
+/// How long `load` waits for windows to appear, and how often it looks for them
+pub struct WaitPolicy {
+    /// How long each entry's window is waited for, from when its application was launched (unless the entry sets its own `timeout`)
+    pub entry_timeout: Duration,
+    /// How long the whole load may take, if it is limited
+    pub overall_timeout: Option<Duration>,
+    pub backoff: Backoff,
+    /// The time between the first and second look at the open windows
+    pub initial_interval: Duration,
+    /// The longest time between looks at the open windows
+    pub max_interval: Duration,
+}
+
+impl WaitPolicy {
+    /// Gets the wait policy from the configuration, overridden by the arrangement's settings and then by any command-line flags
+    ///
+    /// `retry_count` and `retry_interval` are kept from before windows were waited for by deadline: unless `wait_timeout` is set (and neither is overridden at a higher level), each window is waited for `retry_count` * `retry_interval` milliseconds
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration to load the arrangement with
+    /// * `settings` - The settings of the arrangement being loaded
+    /// * `retry_count_flag` - Overrides the `retry_count` configuration value
+    /// * `retry_interval_flag` - Overrides the `retry_interval` configuration value
+    /// * `timeout_flag` - How long (in milliseconds) the whole load may take
+    /// * `backoff_flag` - Overrides the `backoff` configuration value
+    pub fn from_config(
+        config: &ConfigData,
+        settings: &ArrangementSettings,
+        retry_count_flag: Option<usize>,
+        retry_interval_flag: Option<usize>,
+        timeout_flag: Option<usize>,
+        backoff_flag: Option<Backoff>,
+    ) -> Self {
+        let retry_count = retry_count_flag
+            .or(settings.retry_count)
+            .unwrap_or(config.retry_count);
+        let retry_interval = retry_interval_flag
+            .or(settings.retry_interval)
+            .unwrap_or(config.retry_interval);
+        // The highest level that sets any of the three decides whether `wait_timeout` is used
+        let entry_timeout = if retry_count_flag.is_some() || retry_interval_flag.is_some() {
+            retry_count.saturating_mul(retry_interval)
+        } else if let Some(wait_timeout) = settings.wait_timeout {
+            wait_timeout
+        } else if settings.retry_count.is_some() || settings.retry_interval.is_some() {
+            retry_count.saturating_mul(retry_interval)
+        } else {
+            config
+                .wait_timeout
+                .unwrap_or(retry_count.saturating_mul(retry_interval))
+        };
+
+        WaitPolicy {
+            entry_timeout: Duration::from_millis(entry_timeout as u64),
+            overall_timeout: timeout_flag.map(|timeout| Duration::from_millis(timeout as u64)),
+            backoff: backoff_flag.or(settings.backoff).unwrap_or(config.backoff),
+            initial_interval: Duration::from_millis(retry_interval as u64),
+            max_interval: Duration::from_millis(
+                config.max_retry_interval.max(retry_interval) as u64
+            ),
+        }
+    }
+
+    /// Gets how long to wait after a look at the open windows before the next one
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - How many looks have been made so far, starting at 1
+    pub fn get_interval(&self, attempt: usize) -> Duration {
+        let exponential = || {
+            let factor = 2u32.saturating_pow(attempt.saturating_sub(1).min(31) as u32);
+            self.initial_interval
+                .saturating_mul(factor)
+                .min(self.max_interval)
+        };
+
+        match self.backoff {
+            Backoff::Fixed => self.initial_interval,
+            Backoff::Exponential => exponential(),
+            Backoff::Jittered => {
+                let interval = exponential();
+                interval.mul_f64(0.5 + fastrand::f64() * 0.5)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_timeout_saturates_instead_of_overflowing() {
+        let config: ConfigData = serde_json::from_str("{}").unwrap();
+        let wait_policy = WaitPolicy::from_config(
+            &config,
+            &ArrangementSettings::default(),
+            Some(usize::MAX),
+            Some(2),
+            None,
+            None,
+        );
+
+        assert_eq!(
+            wait_policy.entry_timeout,
+            Duration::from_millis(usize::MAX as u64)
+        );
+    }
+}
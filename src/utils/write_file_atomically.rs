@@ -0,0 +1,37 @@
+use anyhow::{Context, Result};
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+/// Writes a file by writing a temporary file next to it and renaming it into place, so the file is never left half-written
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to write
+/// * `contents` - What to write to the file
+///
+/// # Errors
+///
+/// Returns an error if the temporary file could not be written or renamed (in which case the original file is unchanged)
+pub fn write_file_atomically(path: &Path, contents: &str) -> Result<()> {
+    let mut temp_file_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_file_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_file_name);
+
+    let result = File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .with_context(|| "Failed to write temporary file")
+        .and_then(|_| {
+            fs::rename(&temp_path, path).with_context(|| "Failed to move temporary file into place")
+        });
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}